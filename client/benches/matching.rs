@@ -0,0 +1,169 @@
+//! Benchmarks searching a large synthetic browser history, run with
+//! `cargo bench --bench matching`.
+
+// Only the matcher is benchmarked, the other parts of the model are unused
+#![allow(dead_code)]
+
+#[path = "../src/plugin/matcher.rs"]
+mod matcher;
+#[path = "../src/model.rs"]
+mod model;
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const ENTRIES: usize = 200_000;
+const RUNS: usize = 10;
+/// The number of entries the window shows at most in the benchmarks with a limit.
+const LIMIT: usize = 50;
+
+const WORDS: &[&str] = &[
+    "rust",
+    "release",
+    "notes",
+    "github",
+    "issue",
+    "pull",
+    "request",
+    "wayland",
+    "compositor",
+    "layer",
+    "shell",
+    "nix",
+    "flake",
+    "home",
+    "manager",
+    "fuzzy",
+    "matcher",
+    "search",
+    "docs",
+    "tutorial",
+    "async",
+    "channel",
+    "thread",
+    "egui",
+    "font",
+    "theme",
+    "config",
+    "weather",
+    "news",
+    "recipe",
+    "video",
+    "music",
+    "map",
+    "mail",
+    "calendar",
+    "bank",
+    "shop",
+    "forum",
+];
+const DOMAINS: &[&str] = &[
+    "github.com",
+    "docs.rs",
+    "crates.io",
+    "nixos.org",
+    "wikipedia.org",
+    "youtube.com",
+    "reddit.com",
+    "news.ycombinator.com",
+    "example.com",
+    "archlinux.org",
+];
+
+/// History entries with titles and urls built from random words, the same on every run.
+fn history(count: usize) -> Vec<model::Entry> {
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut random = move |bound: usize| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % bound as u64) as usize
+    };
+
+    (0..count)
+        .map(|index| {
+            let words: Vec<&str> = (0..3 + random(6))
+                .map(|_| WORDS[random(WORDS.len())])
+                .collect();
+            let domain = DOMAINS[random(DOMAINS.len())];
+            let url = format!("https://{domain}/{}/{index}", words.join("-"));
+            model::Entry {
+                id: url.clone(),
+                title: words.join(" "),
+                action: String::from("open"),
+                meta: url,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Median duration of running the function.
+fn measure(mut function: impl FnMut()) -> Duration {
+    let mut durations: Vec<Duration> = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            function();
+            start.elapsed()
+        })
+        .collect();
+    durations.sort();
+    durations[RUNS / 2]
+}
+
+fn report(name: &str, duration: Duration) {
+    println!("{name:<48} {:>10.2} ms", duration.as_secs_f64() * 1000.);
+}
+
+fn main() {
+    let entries = history(ENTRIES);
+    let frecency_scores: HashMap<String, u32> = entries
+        .iter()
+        .step_by(100)
+        .map(|entry| (entry.id.clone(), 10))
+        .collect();
+    let typed = [
+        "r", "ru", "rus", "rust", "rust ", "rust r", "rust re", "rust rel",
+    ];
+    println!("{ENTRIES} entries, median of {RUNS} runs");
+
+    report(
+        "prepare entries",
+        measure(|| {
+            std::hint::black_box(matcher::EntryMatcher::new(entries.clone()));
+        }),
+    );
+
+    let mut entry_matcher = matcher::EntryMatcher::new(entries.clone());
+    for (name, limit) in [("all matches", None), ("top 50", Some(LIMIT))] {
+        report(
+            &format!("search 'rust', {name}"),
+            measure(|| {
+                // A different previous query, so every entry is scored
+                entry_matcher.search("x", limit, &frecency_scores);
+                std::hint::black_box(entry_matcher.search("rust", limit, &frecency_scores));
+            }),
+        );
+    }
+
+    report(
+        "typing 'rust rel', top 50, narrowing",
+        measure(|| {
+            entry_matcher.search("", Some(LIMIT), &frecency_scores);
+            for query in typed {
+                std::hint::black_box(entry_matcher.search(query, Some(LIMIT), &frecency_scores));
+            }
+        }),
+    );
+
+    // Like searching before the entries were kept prepared between searches
+    report(
+        "typing 'rust rel', all matches, unprepared",
+        measure(|| {
+            for query in typed {
+                let mut entry_matcher = matcher::EntryMatcher::new(entries.clone());
+                std::hint::black_box(entry_matcher.search(query, None, &frecency_scores));
+            }
+        }),
+    );
+}
@@ -0,0 +1,51 @@
+/// Reports the problems of the config file, returns the exit code of `check-config`.
+pub fn check_config() -> i32 {
+    let check = match check() {
+        Ok(check) => check,
+        Err(error) => {
+            eprintln!("{:#}", error);
+            return 1;
+        }
+    };
+
+    if !std::path::Path::new(&check.config_file_path).exists() {
+        println!(
+            "Config file '{}' not found, the defaults are used.",
+            check.config_file_path
+        );
+        return 0;
+    }
+    if check.diagnostics.is_empty() {
+        println!("No problems found in '{}'.", check.config_file_path);
+        return 0;
+    }
+    eprint!("{}", check.report());
+    // Warnings are reported but don't stop centerpiece either
+    if check.has_errors() { 1 } else { 0 }
+}
+
+/// Reports the problems of the config file, exits if the settings can't be used.
+///
+/// Warnings are reported but don't stop centerpiece.
+pub fn check_on_startup() {
+    let check = match check() {
+        Ok(check) => check,
+        Err(error) => {
+            eprintln!("{:#}", error);
+            std::process::exit(1);
+        }
+    };
+
+    eprint!("{}", check.report());
+    if check.has_errors() {
+        eprintln!(
+            "Please fix the errors in the config file, `centerpiece check-config` checks it again."
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Checks the config file including the config sections of the plugins.
+fn check() -> anyhow::Result<settings::check::Check> {
+    settings::check::Check::of(&settings::config_file_path()?)
+}
@@ -0,0 +1,30 @@
+/// Shows the actions of the active entry, returns the index of a clicked action.
+pub fn view(
+    ui: &mut egui::Ui,
+    action_titles: &[String],
+    active_action_index: usize,
+    theme: &crate::theme::Theme,
+) -> Option<usize> {
+    egui::Frame::NONE
+        .outer_margin(egui::epaint::MarginF32 {
+            left: 1.25 * crate::theme::rem(),
+            right: 1.25 * crate::theme::rem(),
+            top: 1. * crate::theme::rem(),
+            bottom: 0.5 * crate::theme::rem(),
+        })
+        .show(ui, |ui| {
+            ui.heading("Actions");
+        });
+
+    let mut clicked_action_index = None;
+    for (index, action_title) in action_titles.iter().enumerate() {
+        let response = crate::component::row::view(ui, index == active_action_index, theme, |ui| {
+            ui.set_width(ui.available_width());
+            ui.add(egui::Label::new(action_title.clone()).truncate());
+        });
+        if response.clicked() {
+            clicked_action_index = Some(index);
+        }
+    }
+    clicked_action_index
+}
@@ -1,29 +1,152 @@
-pub fn view(ui: &mut egui::Ui, entry: &crate::model::Entry, active: bool) {
-    let settings = settings::Settings::get_or_init();
-    let stroke_color = if active {
-        settings::hexcolor(&settings.color.text)
-    } else {
-        egui::Color32::TRANSPARENT
-    };
-    let stroke = egui::Stroke::new(1., stroke_color);
+use unicode_segmentation::UnicodeSegmentation;
 
-    egui::Frame::new()
-        .stroke(stroke)
-        .corner_radius(0.1 * crate::REM)
-        .inner_margin(0.5 * crate::REM)
-        .outer_margin(egui::vec2(1. * crate::REM, 0.))
-        .show(ui, |ui| {
-            egui::containers::Sides::new().show(
-                ui,
-                |ui| {
-                    ui.set_max_width(700.);
-                    ui.add(egui::Label::new(entry.title.clone()).truncate());
-                },
-                |ui| {
-                    if active {
-                        ui.label(entry.action.clone());
+pub fn view(
+    ui: &mut egui::Ui,
+    entry: &crate::model::Entry,
+    active: bool,
+    badge: Option<String>,
+    icons: &crate::icon::Icons,
+    theme: &crate::theme::Theme,
+) -> egui::Response {
+    crate::component::row::view(ui, active, theme, |ui| {
+        egui::containers::Sides::new().show(
+            ui,
+            |ui| {
+                ui.set_max_width(700.);
+                if let Some(icon) = &entry.icon
+                    && icons.enabled()
+                {
+                    // Keep the space of missing icons to align the titles
+                    let size = egui::Vec2::splat(crate::icon::size());
+                    match icons.get(icon) {
+                        Some(texture) => {
+                            ui.add(egui::Image::new(&texture).fit_to_exact_size(size));
+                        }
+                        None => {
+                            ui.allocate_space(size);
+                        }
                     }
-                },
+                }
+                ui.vertical(|ui| {
+                    let title = match &entry.highlight {
+                        crate::model::Highlight::Title(indices) => {
+                            highlighted(ui, &entry.title, indices, theme.accent).into()
+                        }
+                        _ => egui::WidgetText::from(entry.title.clone()),
+                    };
+                    ui.add(egui::Label::new(title).truncate());
+                    if let Some(subtitle) = &entry.subtitle {
+                        ui.add(
+                            egui::Label::new(egui::RichText::new(subtitle).small().weak())
+                                .truncate(),
+                        );
+                    }
+                });
+            },
+            |ui| {
+                if active {
+                    ui.label(entry.action.clone());
+                }
+                // Explains matches which are not visible in the title
+                if let crate::model::Highlight::Meta(indices) = &entry.highlight {
+                    ui.label(
+                        egui::RichText::new(matched_words(&entry.meta, indices))
+                            .weak()
+                            .italics(),
+                    );
+                }
+                if let Some(badge) = badge {
+                    ui.label(egui::RichText::new(badge).weak());
+                }
+            },
+        );
+    })
+}
+
+/// The text with the characters at the indices underlined in the accent color.
+fn highlighted(
+    ui: &egui::Ui,
+    text: &str,
+    indices: &[u32],
+    accent: egui::Color32,
+) -> egui::text::LayoutJob {
+    let font_id = egui::TextStyle::Body.resolve(ui.style());
+    let mut job = egui::text::LayoutJob::default();
+
+    let mut section = String::new();
+    let mut section_matched = false;
+    for (index, character) in matcher_characters(text).into_iter().enumerate() {
+        let matched = indices.binary_search(&(index as u32)).is_ok();
+        if matched != section_matched && !section.is_empty() {
+            append(
+                &mut job,
+                &std::mem::take(&mut section),
+                &font_id,
+                section_matched.then_some(accent),
             );
-        });
+        }
+        section.push_str(character);
+        section_matched = matched;
+    }
+    append(
+        &mut job,
+        &section,
+        &font_id,
+        section_matched.then_some(accent),
+    );
+
+    job
+}
+
+/// Appends the text, highlighted in the color if given.
+fn append(
+    job: &mut egui::text::LayoutJob,
+    text: &str,
+    font_id: &egui::FontId,
+    highlight: Option<egui::Color32>,
+) {
+    let (color, underline) = match highlight {
+        Some(color) => (color, egui::Stroke::new(1., color)),
+        // Painted in the text color of the label
+        None => (egui::Color32::PLACEHOLDER, egui::Stroke::NONE),
+    };
+    job.append(
+        text,
+        0.,
+        egui::TextFormat {
+            font_id: font_id.clone(),
+            color,
+            underline,
+            ..Default::default()
+        },
+    );
+}
+
+/// The words of the text containing a character at one of the indices.
+fn matched_words(text: &str, indices: &[u32]) -> String {
+    let characters = matcher_characters(text);
+    let mut words = vec![];
+    let mut start = 0;
+    for word in characters.split(|character| *character == " ") {
+        let range = start..start + word.len() as u32;
+        if !word.is_empty() && indices.iter().any(|index| range.contains(index)) {
+            words.push(word.concat());
+        }
+        start = range.end + 1;
+    }
+    words.join(" ")
+}
+
+/// The characters of the text the way the matcher counts them for the indices of a match.
+///
+/// Ascii text is counted by byte, other text by grapheme cluster, e.g. a letter followed by
+/// combining marks or an emoji joined from several emojis.
+fn matcher_characters(text: &str) -> Vec<&str> {
+    if text.is_ascii() {
+        (0..text.len())
+            .map(|index| &text[index..index + 1])
+            .collect()
+    } else {
+        text.graphemes(true).collect()
+    }
 }
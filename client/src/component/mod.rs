@@ -1,3 +1,6 @@
+pub mod action_menu;
 pub mod entry;
 pub mod plugin_header;
+pub mod plugin_status;
 pub mod query_input;
+pub mod row;
@@ -1,10 +1,10 @@
 pub fn view(ui: &mut egui::Ui, plugin: &crate::model::Plugin) {
     egui::Frame::NONE
         .outer_margin(egui::epaint::MarginF32 {
-            left: 1.25 * crate::REM,
-            right: 1.25 * crate::REM,
-            top: 1. * crate::REM,
-            bottom: 0.5 * crate::REM,
+            left: 1.25 * crate::theme::rem(),
+            right: 1.25 * crate::theme::rem(),
+            top: 1. * crate::theme::rem(),
+            bottom: 0.5 * crate::theme::rem(),
         })
         .show(ui, |ui| {
             ui.heading(plugin.title.clone());
@@ -0,0 +1,17 @@
+pub fn view(ui: &mut egui::Ui, plugin: &crate::model::Plugin) {
+    let Some(status) = &plugin.status else {
+        return;
+    };
+
+    egui::Frame::NONE
+        .inner_margin(0.5 * crate::theme::rem())
+        .outer_margin(egui::vec2(1. * crate::theme::rem(), 0.))
+        .show(ui, |ui| {
+            ui.add(
+                egui::Label::new(
+                    egui::RichText::new(format!("{}: {}", plugin.title, status)).weak(),
+                )
+                .truncate(),
+            );
+        });
+}
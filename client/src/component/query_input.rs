@@ -1,15 +1,34 @@
-pub fn view(ui: &mut egui::Ui, query: &mut String) -> egui::Response {
-    ui.add(
-        egui::TextEdit::singleline(query)
-            .hint_text("Search")
-            .lock_focus(true)
-            .desired_width(f32::INFINITY)
-            .frame(false)
-            .margin(egui::epaint::MarginF32 {
-                left: 1. * crate::REM,
-                right: 1. * crate::REM,
-                top: 1. * crate::REM,
-                bottom: 0.75 * crate::REM,
-            }),
-    )
+pub fn view(ui: &mut egui::Ui, query: &mut String, scope: Option<String>) -> egui::Response {
+    let size = egui::vec2(ui.available_width(), ui.spacing().interact_size.y);
+    let layout = egui::Layout::right_to_left(egui::Align::Center);
+    ui.allocate_ui_with_layout(size, layout, |ui| {
+        // The scope is added first to reserve its space right of the input
+        if let Some(scope) = scope {
+            egui::Frame::NONE
+                .inner_margin(egui::epaint::MarginF32 {
+                    left: 0.,
+                    right: 1. * crate::theme::rem(),
+                    top: 1. * crate::theme::rem(),
+                    bottom: 0.75 * crate::theme::rem(),
+                })
+                .show(ui, |ui| {
+                    ui.label(egui::RichText::new(scope).weak());
+                });
+        }
+
+        ui.add(
+            egui::TextEdit::singleline(query)
+                .hint_text("Search")
+                .lock_focus(true)
+                .desired_width(f32::INFINITY)
+                .frame(false)
+                .margin(egui::epaint::MarginF32 {
+                    left: 1. * crate::theme::rem(),
+                    right: 1. * crate::theme::rem(),
+                    top: 1. * crate::theme::rem(),
+                    bottom: 0.75 * crate::theme::rem(),
+                }),
+        )
+    })
+    .inner
 }
@@ -0,0 +1,33 @@
+/// A framed row of the result list or the action menu which can be hovered and clicked.
+///
+/// The active row is outlined in the selection color, a hovered row in a faded selection color.
+pub fn view(
+    ui: &mut egui::Ui,
+    active: bool,
+    theme: &crate::theme::Theme,
+    add_contents: impl FnOnce(&mut egui::Ui),
+) -> egui::Response {
+    let mut prepared = egui::Frame::new()
+        .corner_radius(theme.row_corner_radius)
+        .inner_margin(theme.row_padding)
+        .outer_margin(egui::vec2(1. * crate::theme::rem(), 0.))
+        .begin(ui);
+    add_contents(&mut prepared.content_ui);
+
+    let allocated = prepared.allocate_space(ui);
+    // The outer margin belongs to the panel, not to the row
+    let rect = prepared.frame.widget_rect(prepared.content_ui.min_rect());
+    let response = ui.interact(rect, allocated.id.with("row"), egui::Sense::click());
+
+    let stroke_color = if active {
+        theme.selection
+    } else if response.hovered() {
+        theme.selection.gamma_multiply(0.3)
+    } else {
+        egui::Color32::TRANSPARENT
+    };
+    prepared.frame.stroke = egui::Stroke::new(1., stroke_color);
+    prepared.paint(ui);
+
+    response
+}
@@ -0,0 +1,58 @@
+use anyhow::Context;
+use inotify::{Inotify, WatchMask};
+use std::path::Path;
+
+/// Watches the config file in a background thread and calls `on_change` after it was written,
+/// replaced or removed.
+///
+/// The directory of the config file is watched, editors often replace the file instead of
+/// writing to it.
+pub fn watch<F>(on_change: F) -> anyhow::Result<()>
+where
+    F: Fn() + Send + 'static,
+{
+    let config_file_path = settings::config_file_path()?;
+    let config_file_path = Path::new(&config_file_path);
+    let directory = config_file_path
+        .parent()
+        .context("Config file has no parent directory.")?;
+    let file_name = config_file_path
+        .file_name()
+        .context("Config file path has no file name.")?
+        .to_os_string();
+
+    let mut inotify = Inotify::init().context("Failed to initialize inotify.")?;
+    inotify
+        .watches()
+        .add(
+            directory,
+            WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO | WatchMask::DELETE,
+        )
+        .context(format!(
+            "Failed to watch config directory '{}'.",
+            directory.display()
+        ))?;
+    log::info!("Watching {} for changes", config_file_path.display());
+
+    std::thread::spawn(move || {
+        let mut buffer = [0; 4096];
+        loop {
+            let events = match inotify.read_events_blocking(&mut buffer) {
+                Ok(events) => events,
+                Err(error) => {
+                    log::error!("Stopped watching the config file: {error}");
+                    return;
+                }
+            };
+            // One save can cause several events, e.g. writing a file and moving it in place
+            if events
+                .into_iter()
+                .any(|event| event.name == Some(file_name.as_os_str()))
+            {
+                on_change();
+            }
+        }
+    });
+
+    Ok(())
+}
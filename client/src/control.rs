@@ -0,0 +1,79 @@
+use anyhow::Context;
+use settings::cli::ControlCommand;
+use std::env;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+
+const SOCKET_FILE_NAME: &str = "centerpiece.sock";
+
+/// Sends a command to a running centerpiece daemon.
+pub fn send(command: ControlCommand) -> anyhow::Result<()> {
+    let path = socket_path().context("Unable to determine control socket path.")?;
+    let mut stream = UnixStream::connect(&path).context(format!(
+        "Failed to connect to centerpiece daemon at '{}'. Is `centerpiece --daemon` running?",
+        path.display()
+    ))?;
+    writeln!(stream, "{}", to_line(command)).context("Failed to send command to daemon.")?;
+    Ok(())
+}
+
+/// Whether a centerpiece daemon listens on the control socket.
+pub fn is_listening() -> bool {
+    socket_path().is_some_and(|path| UnixStream::connect(path).is_ok())
+}
+
+/// Listens for commands on the control socket in a background thread.
+pub fn listen<F>(on_command: F) -> Option<()>
+where
+    F: Fn(ControlCommand) + Send + 'static,
+{
+    let path = socket_path()?;
+    // A socket file left behind by a killed daemon would make bind fail
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)
+        .map_err(|e| log::error!("Failed to bind control socket: {e}"))
+        .ok()?;
+    log::info!("Listening for commands on {}", path.display());
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                match from_line(&line) {
+                    Some(command) => on_command(command),
+                    None => log::warn!("Ignoring unknown control command '{line}'"),
+                }
+            }
+        }
+    });
+
+    Some(())
+}
+
+fn socket_path() -> Option<PathBuf> {
+    let dir = env::var("XDG_RUNTIME_DIR").ok();
+    if dir.is_none() {
+        log::warn!("XDG_RUNTIME_DIR not set, unable to locate control socket");
+    }
+    Some(Path::new(&dir?).join(SOCKET_FILE_NAME))
+}
+
+fn to_line(command: ControlCommand) -> &'static str {
+    match command {
+        ControlCommand::Show => "show",
+        ControlCommand::Hide => "hide",
+        ControlCommand::Toggle => "toggle",
+    }
+}
+
+fn from_line(line: &str) -> Option<ControlCommand> {
+    match line.trim() {
+        "show" => Some(ControlCommand::Show),
+        "hide" => Some(ControlCommand::Hide),
+        "toggle" => Some(ControlCommand::Toggle),
+        _ => None,
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::Context;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+const HISTORY_FILE_NAME: &str = "activation-history.json";
+/// Older activations are dropped to keep the history file small.
+const MAX_ACTIVATIONS_PER_ENTRY: usize = 10;
+const DAY_IN_SECONDS: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct ActivationHistory {
+    /// Unix timestamps of activations by plugin id and entry id
+    activations: HashMap<String, HashMap<String, Vec<u64>>>,
+}
+
+fn history() -> &'static Mutex<ActivationHistory> {
+    static HISTORY: OnceLock<Mutex<ActivationHistory>> = OnceLock::new();
+    HISTORY.get_or_init(|| {
+        let history = read_history_file().unwrap_or_else(|error| {
+            log::info!("Starting with an empty activation history: {:#}", error);
+            ActivationHistory::default()
+        });
+        Mutex::new(history)
+    })
+}
+
+/// Records that an entry was activated and persists the activation history.
+pub fn record(entry_key: &crate::model::EntryKey) {
+    let mut history = history().lock().unwrap_or_else(|error| error.into_inner());
+
+    let activations = history
+        .activations
+        .entry(entry_key.plugin_id.clone())
+        .or_default()
+        .entry(entry_key.entry_id.clone())
+        .or_default();
+    activations.push(now());
+    if activations.len() > MAX_ACTIVATIONS_PER_ENTRY {
+        activations.remove(0);
+    }
+
+    if let Err(error) = write_history_file(&history) {
+        log::warn!("{:?}", error);
+    }
+}
+
+/// Frecency scores of all previously activated entries of a plugin by entry id.
+///
+/// Every activation adds a weight depending on its age, so entries used often and recently
+/// score highest. Scores are in the range of 0 to 100.
+pub fn scores(plugin_id: &str) -> HashMap<String, u32> {
+    let history = history().lock().unwrap_or_else(|error| error.into_inner());
+    let Some(plugin_activations) = history.activations.get(plugin_id) else {
+        return HashMap::new();
+    };
+
+    let now = now();
+    plugin_activations
+        .iter()
+        .map(|(entry_id, activations)| {
+            let weight: u32 = activations
+                .iter()
+                .map(|activation| age_weight(now.saturating_sub(*activation) / DAY_IN_SECONDS))
+                .sum();
+            (entry_id.clone(), weight / MAX_ACTIVATIONS_PER_ENTRY as u32)
+        })
+        .collect()
+}
+
+fn age_weight(age_in_days: u64) -> u32 {
+    match age_in_days {
+        0..4 => 100,
+        4..14 => 70,
+        14..31 => 50,
+        31..90 => 30,
+        _ => 10,
+    }
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn history_file_path() -> anyhow::Result<String> {
+    let cache_directory = settings::centerpiece_cache_directory()?;
+    Ok(format!("{cache_directory}/{HISTORY_FILE_NAME}"))
+}
+
+fn read_history_file() -> anyhow::Result<ActivationHistory> {
+    let history_file = std::fs::File::open(history_file_path()?)
+        .context("Error while opening activation history file")?;
+    let reader = std::io::BufReader::new(history_file);
+    serde_json::from_reader(reader).context("Error while reading activation history file")
+}
+
+fn write_history_file(history: &ActivationHistory) -> anyhow::Result<()> {
+    let cache_directory = settings::centerpiece_cache_directory()?;
+    std::fs::create_dir_all(&cache_directory).context("Error while creating cache directory")?;
+
+    let history_file = std::fs::File::create(history_file_path()?)
+        .context("Error while creating activation history file")?;
+    let writer = std::io::BufWriter::new(history_file);
+    serde_json::to_writer(writer, history).context("Error while writing activation history file")
+}
@@ -0,0 +1,261 @@
+use anyhow::Context;
+use std::time::{Duration, Instant};
+
+/// How long to wait for plugins to register and answer a search.
+const PLUGIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(serde::Serialize)]
+struct QueryResult<'a> {
+    plugin: &'a str,
+    id: &'a str,
+    title: &'a str,
+    subtitle: Option<&'a str>,
+    action: &'a str,
+    meta: &'a str,
+    icon: Option<&'a str>,
+}
+
+/// Prints the entries matching the query in the order they would be displayed in the window.
+pub fn query(query: String, plugin_id: Option<String>, json: bool) -> anyhow::Result<()> {
+    let mut centerpiece = crate::Centerpiece {
+        mode: crate::Mode::Headless,
+        query,
+        plugin_filter: plugin_id,
+        ..Default::default()
+    };
+    centerpiece.launch_plugins();
+    wait_for_entries(&mut centerpiece);
+
+    for plugin in centerpiece.plugins_with_status() {
+        eprintln!(
+            "{}: {}",
+            plugin.title,
+            plugin.status.clone().unwrap_or_default()
+        );
+    }
+
+    let results: Vec<QueryResult> = centerpiece
+        .entries_with_plugin()
+        .into_iter()
+        .map(|(plugin, entry)| QueryResult {
+            plugin: &plugin.id,
+            id: &entry.id,
+            title: &entry.title,
+            subtitle: entry.subtitle.as_deref(),
+            action: &entry.action,
+            meta: &entry.meta,
+            icon: entry.icon.as_deref(),
+        })
+        .collect();
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string(&results).context("Failed to serialize entries.")?
+        );
+        return Ok(());
+    }
+
+    for result in results {
+        println!("{}/{}\t{}", result.plugin, result.id, result.title);
+    }
+    Ok(())
+}
+
+/// Activates the entry addressed by `<plugin>/<entry-id>`, optionally running one of its
+/// additional actions instead of the default one.
+pub fn activate(target: &str, action_id: Option<String>) -> anyhow::Result<()> {
+    let key: crate::model::EntryKey = target.parse()?;
+    let mut centerpiece = send_activation(&key, action_id)?;
+    let crate::model::EntryKey {
+        plugin_id,
+        entry_id,
+    } = key;
+
+    // Plugins request an exit once the entry is activated, which ends the process
+    let mut activation_error = None;
+    handle_messages_until(
+        &mut centerpiece,
+        Instant::now() + PLUGIN_TIMEOUT,
+        |_, message| match message {
+            // Plugins report a failed activation as their status
+            crate::Message::UpdateStatus(_, Some(status)) => {
+                activation_error = Some(status.clone());
+                true
+            }
+            _ => false,
+        },
+    );
+    match activation_error {
+        Some(error) => anyhow::bail!("Failed to activate '{target}': {error}"),
+        None => anyhow::bail!(
+            "Plugin with id '{plugin_id}' did not finish activating entry '{entry_id}' within {} seconds.",
+            PLUGIN_TIMEOUT.as_secs()
+        ),
+    }
+}
+
+/// Launches the plugin of the entry and asks it to activate the entry.
+///
+/// Entries are looked up among all entries of the plugin, the result limits of its instance
+/// only apply to queries.
+fn send_activation(
+    key: &crate::model::EntryKey,
+    action_id: Option<String>,
+) -> anyhow::Result<crate::Centerpiece> {
+    let crate::model::EntryKey {
+        plugin_id,
+        entry_id,
+    } = key;
+
+    let mut centerpiece = crate::Centerpiece {
+        mode: crate::Mode::Headless,
+        plugin_filter: Some(plugin_id.clone()),
+        ignore_result_limits: true,
+        ..Default::default()
+    };
+    centerpiece.launch_plugins();
+    wait_for_entries(&mut centerpiece);
+
+    let plugin = centerpiece
+        .plugins
+        .iter()
+        .find(|plugin| &plugin.id == plugin_id)
+        .context(format!(
+            "Plugin with id '{plugin_id}' is not enabled or failed to start."
+        ))?;
+    let entry = plugin
+        .entries
+        .iter()
+        .find(|entry| &entry.id == entry_id)
+        .cloned()
+        .context(format!(
+            "Plugin with id '{plugin_id}' has no entry with id '{entry_id}'."
+        ))?;
+
+    plugin
+        .app_channel_out
+        .send_blocking(crate::model::PluginRequest::Activate(
+            Box::new(entry),
+            action_id,
+        ))
+        .context(format!(
+            "Failed to send activation request to plugin with id '{plugin_id}'."
+        ))?;
+    Ok(centerpiece)
+}
+
+/// Waits until every launched plugin registered and answered the current query,
+/// crashed, or the timeout passed.
+fn wait_for_entries(centerpiece: &mut crate::Centerpiece) {
+    let deadline = Instant::now() + PLUGIN_TIMEOUT;
+    handle_messages_until(
+        centerpiece,
+        deadline,
+        |centerpiece, message| match message {
+            crate::Message::UpdateEntries(..) => true,
+            // Plugins outside of the scope of the query are not searched
+            crate::Message::RegisterPlugin(plugin) => {
+                centerpiece.plugin_query(&plugin.id).is_none()
+            }
+            // The first update of the entries failed
+            crate::Message::UpdateStatus(_, status) => status.is_some(),
+            crate::Message::Exit => false,
+        },
+    );
+}
+
+fn handle_messages_until(
+    centerpiece: &mut crate::Centerpiece,
+    deadline: Instant,
+    mut is_done: impl FnMut(&crate::Centerpiece, &crate::Message) -> bool,
+) {
+    for plugin_channel in centerpiece.plugin_channels.clone().into_values() {
+        loop {
+            let message_option = smol::block_on(futures_lite::future::or(
+                async { plugin_channel.recv().await.ok() },
+                async {
+                    smol::Timer::at(deadline).await;
+                    None
+                },
+            ));
+            // The plugin thread stopped or the timeout passed
+            let Some(message) = message_option else {
+                break;
+            };
+
+            let done = is_done(centerpiece, &message);
+            centerpiece.handle_messages(vec![message]);
+            if done {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Script plugins offering the entries `one` and `two`, activating an entry exits.
+    const CONFIG: &str = r#"
+plugin:
+  limited:
+    type: script
+    command: ["sh", "-c", "$SCRIPT"]
+    max_results: 1
+  scoped:
+    type: script
+    command: ["sh", "-c", "$SCRIPT"]
+    keywords: ["s"]
+    only_when_scoped: true
+"#;
+
+    const SCRIPT: &str = r#"echo '{\"type\": \"update_entries\", \"entries\": [{\"id\": \"one\", \"title\": \"One\"}, {\"id\": \"two\", \"title\": \"Two\"}]}'; while read -r request; do case \"$request\" in *'\"type\":\"activate\"'*) echo '{\"type\": \"exit\"}';; esac; done"#;
+
+    fn use_test_config() {
+        static CONFIG_FILE: std::sync::Once = std::sync::Once::new();
+        CONFIG_FILE.call_once(|| {
+            let config_file_path = std::env::temp_dir().join(format!(
+                "centerpiece-headless-test-{}.yml",
+                std::process::id()
+            ));
+            std::fs::write(&config_file_path, CONFIG.replace("$SCRIPT", SCRIPT)).unwrap();
+            settings::set_config_file_path(config_file_path.to_string_lossy().into_owned());
+        });
+    }
+
+    /// Sends the activation of the entry and waits for the plugin to exit after activating it.
+    fn assert_activates(target: &str) {
+        use_test_config();
+        let key: crate::model::EntryKey = target.parse().unwrap();
+        let centerpiece = send_activation(&key, None).unwrap();
+
+        let plugin_channel = &centerpiece.plugin_channels[&key.plugin_id];
+        let deadline = Instant::now() + PLUGIN_TIMEOUT;
+        loop {
+            let message = smol::block_on(futures_lite::future::or(
+                async { plugin_channel.recv().await.ok() },
+                async {
+                    smol::Timer::at(deadline).await;
+                    None
+                },
+            ));
+            match message {
+                Some(crate::Message::Exit) => return,
+                Some(_) => continue,
+                None => panic!("Entry '{target}' was not activated."),
+            }
+        }
+    }
+
+    #[test]
+    fn activate_entry_beyond_max_results() {
+        assert_activates("limited/two");
+    }
+
+    #[test]
+    fn activate_entry_of_plugin_only_shown_when_scoped() {
+        assert_activates("scoped/one");
+    }
+}
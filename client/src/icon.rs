@@ -0,0 +1,451 @@
+use anyhow::Context;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+/// Width and height of icons in the window.
+pub fn size() -> f32 {
+    crate::theme::rem()
+}
+
+/// Theme every icon theme falls back to.
+const FALLBACK_THEME: &str = "hicolor";
+const EXTENSIONS: [&str; 2] = ["png", "svg"];
+
+/// Textures of the icons shown next to entries.
+///
+/// Icons are looked up and rasterized on a background thread, rows show an empty space until
+/// their icon arrived.
+#[derive(Default)]
+pub struct Icons {
+    /// Textures by icon name, `None` while loading or when no icon was found
+    textures: RefCell<HashMap<String, Option<egui::TextureHandle>>>,
+    loader: Option<Loader>,
+}
+
+struct Loader {
+    requests: mpsc::Sender<String>,
+    responses: mpsc::Receiver<(String, Option<egui::ColorImage>)>,
+}
+
+impl Icons {
+    pub fn enabled(&self) -> bool {
+        settings::Settings::get_or_init().icon.enable
+    }
+
+    /// Starts the loader and turns loaded icons into textures, called once per frame.
+    pub fn update(&mut self, ctx: &egui::Context) {
+        if !self.enabled() {
+            return;
+        }
+
+        let loader = self.loader.get_or_insert_with(|| {
+            let size = (size() * ctx.pixels_per_point()).round() as u32;
+            Loader::spawn(ctx.clone(), size)
+        });
+        for (name, image) in loader.responses.try_iter() {
+            let texture = image.map(|image| {
+                ctx.load_texture(format!("icon-{name}"), image, egui::TextureOptions::LINEAR)
+            });
+            self.textures.borrow_mut().insert(name, texture);
+        }
+    }
+
+    /// The texture of an icon, requested from the loader the first time it is needed.
+    pub fn get(&self, name: &str) -> Option<egui::TextureHandle> {
+        let loader = self.loader.as_ref()?;
+        let mut textures = self.textures.borrow_mut();
+        if let Some(texture) = textures.get(name) {
+            return texture.clone();
+        }
+
+        textures.insert(String::from(name), None);
+        let _ = loader.requests.send(String::from(name));
+        None
+    }
+}
+
+impl Loader {
+    fn spawn(ctx: egui::Context, size: u32) -> Self {
+        let (requests, request_receiver) = mpsc::channel::<String>();
+        let (response_sender, responses) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            let mut lookup = Lookup::new(settings::Settings::get_or_init().icon.theme.clone());
+            for name in request_receiver {
+                let image = match lookup.load(&name, size) {
+                    Ok(image) => image,
+                    Err(error) => {
+                        log::warn!("Failed to load icon '{}': {:#}", name, error);
+                        None
+                    }
+                };
+                if response_sender.send((name, image)).is_err() {
+                    break;
+                }
+                ctx.request_repaint();
+            }
+        });
+
+        Self {
+            requests,
+            responses,
+        }
+    }
+}
+
+/// Finds icons following the freedesktop icon theme specification.
+struct Lookup {
+    theme: Option<String>,
+    /// Parsed themes by name, `None` for themes which are not installed
+    themes: HashMap<String, Option<Theme>>,
+}
+
+struct Theme {
+    /// The directory of the theme in every base directory which contains it
+    directories: Vec<PathBuf>,
+    subdirectories: Vec<Subdirectory>,
+    inherits: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum SubdirectoryType {
+    Fixed,
+    Scalable,
+    Threshold,
+}
+
+struct Subdirectory {
+    path: String,
+    kind: SubdirectoryType,
+    /// Sizes in pixels, multiplied with the scale of the directory
+    size: u32,
+    min_size: u32,
+    max_size: u32,
+    threshold: u32,
+}
+
+impl Subdirectory {
+    fn matches_size(&self, size: u32) -> bool {
+        match self.kind {
+            SubdirectoryType::Fixed => self.size == size,
+            SubdirectoryType::Scalable => (self.min_size..=self.max_size).contains(&size),
+            SubdirectoryType::Threshold => {
+                self.size.saturating_sub(self.threshold) <= size
+                    && size <= self.size + self.threshold
+            }
+        }
+    }
+
+    fn size_distance(&self, size: u32) -> u32 {
+        let (min_size, max_size) = match self.kind {
+            SubdirectoryType::Fixed => (self.size, self.size),
+            SubdirectoryType::Scalable => (self.min_size, self.max_size),
+            SubdirectoryType::Threshold => (
+                self.size.saturating_sub(self.threshold),
+                self.size + self.threshold,
+            ),
+        };
+        min_size.saturating_sub(size) + size.saturating_sub(max_size)
+    }
+}
+
+impl Lookup {
+    fn new(theme: Option<String>) -> Self {
+        Self {
+            theme,
+            themes: HashMap::new(),
+        }
+    }
+
+    /// Rasterizes an icon, reusing the cached image unless the icon changed since.
+    fn load(&mut self, name: &str, size: u32) -> anyhow::Result<Option<egui::ColorImage>> {
+        let Some(path) = self.find(name, size) else {
+            log::debug!("No icon found for '{}'", name);
+            return Ok(None);
+        };
+
+        let pixmap = match read_cache(&path, size) {
+            Some(pixmap) => pixmap,
+            None => {
+                let pixmap = rasterize(&path, size)?;
+                if let Err(error) = write_cache(&path, size, &pixmap) {
+                    log::warn!("{:#}", error);
+                }
+                pixmap
+            }
+        };
+
+        Ok(Some(egui::ColorImage::from_rgba_premultiplied(
+            [pixmap.width() as usize, pixmap.height() as usize],
+            pixmap.data(),
+        )))
+    }
+
+    fn find(&mut self, name: &str, size: u32) -> Option<PathBuf> {
+        if name.starts_with('/') {
+            return Some(PathBuf::from(name)).filter(|path| path.is_file());
+        }
+
+        self.find_in_themes(name, size).or_else(|| {
+            // Windows are named after the desktop file of their app, which names the icon
+            let icon = desktop_file_icon(name)?;
+            if icon.starts_with('/') {
+                return Some(PathBuf::from(icon)).filter(|path| path.is_file());
+            }
+            self.find_in_themes(&icon, size)
+        })
+    }
+
+    fn find_in_themes(&mut self, name: &str, size: u32) -> Option<PathBuf> {
+        let mut themes: Vec<String> = self.theme.iter().cloned().collect();
+        themes.push(String::from(FALLBACK_THEME));
+        let mut visited = vec![];
+        for theme in themes {
+            if let Some(path) = self.find_in_theme(&theme, name, size, &mut visited) {
+                return Some(path);
+            }
+        }
+
+        data_directories()
+            .into_iter()
+            .map(|directory| directory.join("pixmaps"))
+            .find_map(|directory| find_file(&directory, name))
+    }
+
+    /// Searches a theme and the themes it inherits from, the closest size wins.
+    fn find_in_theme(
+        &mut self,
+        theme_name: &str,
+        name: &str,
+        size: u32,
+        visited: &mut Vec<String>,
+    ) -> Option<PathBuf> {
+        if visited.iter().any(|visited| visited == theme_name) {
+            return None;
+        }
+        visited.push(String::from(theme_name));
+
+        let theme = self
+            .themes
+            .entry(String::from(theme_name))
+            .or_insert_with(|| Theme::read(theme_name))
+            .as_ref()?;
+
+        let mut closest: Option<(u32, PathBuf)> = None;
+        for subdirectory in theme.subdirectories.iter() {
+            let distance = subdirectory.size_distance(size);
+            if closest
+                .as_ref()
+                .is_some_and(|(closest_distance, _)| *closest_distance <= distance)
+            {
+                continue;
+            }
+            let path = theme
+                .directories
+                .iter()
+                .find_map(|directory| find_file(&directory.join(&subdirectory.path), name));
+            if let Some(path) = path {
+                if subdirectory.matches_size(size) {
+                    return Some(path);
+                }
+                closest = Some((distance, path));
+            }
+        }
+        if let Some((_, path)) = closest {
+            return Some(path);
+        }
+
+        let inherits = theme.inherits.clone();
+        inherits
+            .iter()
+            .filter(|inherited| *inherited != FALLBACK_THEME)
+            .find_map(|inherited| self.find_in_theme(inherited, name, size, visited))
+    }
+}
+
+impl Theme {
+    fn read(name: &str) -> Option<Self> {
+        let directories: Vec<PathBuf> = base_directories()
+            .into_iter()
+            .map(|directory| directory.join(name))
+            .filter(|directory| directory.is_dir())
+            .collect();
+        let index = directories
+            .iter()
+            .find_map(|directory| read_ini(&directory.join("index.theme")))?;
+        let theme_section = index.get("Icon Theme")?;
+
+        let list = |key: &str| -> Vec<String> {
+            theme_section
+                .get(key)
+                .map(|value| {
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|item| !item.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let mut subdirectory_paths = list("Directories");
+        subdirectory_paths.extend(list("ScaledDirectories"));
+        let subdirectories = subdirectory_paths
+            .into_iter()
+            .filter_map(|path| {
+                let section = index.get(&path)?;
+                let number = |key: &str| section.get(key)?.parse::<u32>().ok();
+                let scale = number("Scale").unwrap_or(1);
+                let size = number("Size")? * scale;
+                let kind = match section.get("Type").map(String::as_str) {
+                    Some("Fixed") => SubdirectoryType::Fixed,
+                    Some("Scalable") => SubdirectoryType::Scalable,
+                    _ => SubdirectoryType::Threshold,
+                };
+                Some(Subdirectory {
+                    path,
+                    kind,
+                    size,
+                    min_size: number("MinSize").map_or(size, |min_size| min_size * scale),
+                    max_size: number("MaxSize").map_or(size, |max_size| max_size * scale),
+                    threshold: number("Threshold").unwrap_or(2) * scale,
+                })
+            })
+            .collect();
+
+        Some(Self {
+            directories,
+            subdirectories,
+            inherits: list("Inherits"),
+        })
+    }
+}
+
+fn find_file(directory: &Path, name: &str) -> Option<PathBuf> {
+    EXTENSIONS
+        .iter()
+        .map(|extension| directory.join(format!("{name}.{extension}")))
+        .find(|path| path.is_file())
+}
+
+/// `$XDG_DATA_HOME` followed by `$XDG_DATA_DIRS`.
+fn data_directories() -> Vec<PathBuf> {
+    let home_directory = std::env::var("HOME").unwrap_or_default();
+    let data_home =
+        std::env::var("XDG_DATA_HOME").unwrap_or_else(|_| format!("{home_directory}/.local/share"));
+    let data_directories = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|directories| !directories.is_empty())
+        .unwrap_or_else(|| String::from("/usr/local/share:/usr/share"));
+
+    std::iter::once(data_home.as_str())
+        .chain(data_directories.split(':'))
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Directories containing icon themes in order of precedence.
+fn base_directories() -> Vec<PathBuf> {
+    let home_directory = std::env::var("HOME").unwrap_or_default();
+    std::iter::once(PathBuf::from(format!("{home_directory}/.icons")))
+        .chain(
+            data_directories()
+                .into_iter()
+                .map(|directory| directory.join("icons")),
+        )
+        .collect()
+}
+
+fn desktop_file_icon(app_id: &str) -> Option<String> {
+    data_directories().into_iter().find_map(|directory| {
+        let desktop_file = read_ini(&directory.join(format!("applications/{app_id}.desktop")))?;
+        desktop_file.get("Desktop Entry")?.get("Icon").cloned()
+    })
+}
+
+/// Sections of an ini file like `index.theme` or a desktop file by section name.
+fn read_ini(path: &Path) -> Option<HashMap<String, HashMap<String, String>>> {
+    let contents = std::fs::read_to_string(path).ok()?;
+
+    let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut section = String::new();
+    for line in contents.lines().map(str::trim) {
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            section = String::from(name);
+        } else if let Some((key, value)) = line.split_once('=')
+            && !line.starts_with('#')
+        {
+            sections
+                .entry(section.clone())
+                .or_default()
+                .insert(String::from(key.trim()), String::from(value.trim()));
+        }
+    }
+    Some(sections)
+}
+
+fn rasterize(path: &Path, size: u32) -> anyhow::Result<tiny_skia::Pixmap> {
+    let mut pixmap = tiny_skia::Pixmap::new(size, size).context("Icon size must not be zero")?;
+
+    if path.extension().is_some_and(|extension| extension == "svg") {
+        let data = std::fs::read(path).context(format!("Error while reading icon {path:?}"))?;
+        let tree = resvg::usvg::Tree::from_data(&data, &resvg::usvg::Options::default())
+            .context(format!("Error while parsing icon {path:?}"))?;
+        let transform = fit_transform(tree.size().width(), tree.size().height(), size);
+        resvg::render(&tree, transform, &mut pixmap.as_mut());
+    } else {
+        let image = tiny_skia::Pixmap::load_png(path)
+            .context(format!("Error while decoding icon {path:?}"))?;
+        let transform = fit_transform(image.width() as f32, image.height() as f32, size);
+        let paint = tiny_skia::PixmapPaint {
+            quality: tiny_skia::FilterQuality::Bicubic,
+            ..Default::default()
+        };
+        pixmap.draw_pixmap(0, 0, image.as_ref(), &paint, transform, None);
+    }
+
+    Ok(pixmap)
+}
+
+/// Scales an image to fit into a square of the given size and centers it.
+fn fit_transform(width: f32, height: f32, size: u32) -> tiny_skia::Transform {
+    let size = size as f32;
+    let scale = size / width.max(height);
+    tiny_skia::Transform::from_scale(scale, scale)
+        .post_translate((size - width * scale) / 2., (size - height * scale) / 2.)
+}
+
+fn cache_file_path(path: &Path, size: u32) -> anyhow::Result<PathBuf> {
+    let cache_directory = settings::centerpiece_cache_directory()?;
+    let file_name = path.to_string_lossy().replace('/', "%");
+    Ok(PathBuf::from(format!(
+        "{cache_directory}/icons/{size}/{file_name}.png"
+    )))
+}
+
+/// The cached image of an icon, unless the icon was modified after it was cached.
+fn read_cache(path: &Path, size: u32) -> Option<tiny_skia::Pixmap> {
+    let cache_file_path = cache_file_path(path, size).ok()?;
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|metadata| metadata.modified());
+    if modified(path).ok()? > modified(&cache_file_path).ok()? {
+        return None;
+    }
+    tiny_skia::Pixmap::load_png(cache_file_path).ok()
+}
+
+fn write_cache(path: &Path, size: u32, pixmap: &tiny_skia::Pixmap) -> anyhow::Result<()> {
+    let cache_file_path = cache_file_path(path, size)?;
+    if let Some(cache_directory) = cache_file_path.parent() {
+        std::fs::create_dir_all(cache_directory)
+            .context("Error while creating icon cache directory")?;
+    }
+    pixmap
+        .save_png(&cache_file_path)
+        .context("Error while writing icon cache file")
+}
@@ -1,49 +1,130 @@
 use clap::Parser;
 use egui::{self, Separator};
 
+mod check;
 mod component;
+mod config_watcher;
+mod control;
+mod frecency;
+mod headless;
+mod icon;
 mod lock;
 mod model;
 mod plugin;
+mod theme;
+mod window;
 
 const APP_ID: &str = "centerpiece";
-use smithay_client_toolkit::shell::WaylandSurface;
+use settings::keybindings::KeyCommand;
+use smithay_client_toolkit::seat::pointer::PointerEventKind;
 
 pub fn main() {
     let args = settings::cli::CliArgs::parse();
-    settings::Settings::try_from(args).unwrap_or_else(|_| {
-        eprintln!("There is an issue with the settings, please check the configuration file.");
-        std::process::exit(1);
-    });
+
+    if let Some(settings::cli::Command::Control(command)) = args.command {
+        if let Err(error) = control::send(command) {
+            eprintln!("{:#}", error);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(config_file_path) = args.config.clone() {
+        settings::set_config_file_path(config_file_path);
+    }
+    // Every check of the config file, e.g. on reload, checks the plugin sections as well
+    settings::check::set_plugin_check(plugin::registry::check);
+    if let Some(settings::cli::Command::CheckConfig) = args.command {
+        std::process::exit(check::check_config());
+    }
+    check::check_on_startup();
 
     simple_logger::init_with_level(log::Level::Info).unwrap();
 
-    let _lock = lock::LockFile::acquire();
+    let headless_result = match args.command.clone() {
+        Some(settings::cli::Command::Query {
+            query,
+            plugin,
+            json,
+        }) => Some(headless::query(query, plugin, json)),
+        Some(settings::cli::Command::Activate { target, action }) => {
+            Some(headless::activate(&target, action))
+        }
+        _ => None,
+    };
+    if let Some(result) = headless_result {
+        if let Err(error) = result {
+            eprintln!("{:#}", error);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mode = if args.daemon {
+        Mode::Daemon
+    } else if args.dmenu {
+        Mode::Dmenu {
+            print_query: args.print_query,
+        }
+    } else {
+        Mode::Standalone
+    };
+
+    match mode {
+        // Opening the window of a running daemon keeps its indexed results
+        Mode::Standalone if control::is_listening() => {
+            if let Err(error) = control::send(settings::cli::ControlCommand::Show) {
+                log::error!("{:?}", error);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Mode::Daemon if control::is_listening() => {
+            log::error!("A centerpiece daemon is already running.");
+            std::process::exit(1);
+        }
+        _ => {}
+    }
+
+    // Only a standalone launcher replaces a running one, the daemon and dmenu pickers don't
+    let _lock = match mode {
+        Mode::Standalone => lock::LockFile::acquire(),
+        _ => None,
+    };
 
     // Create channel for external events
     let (tx, rx) = std::sync::mpsc::channel::<AppEvent>();
 
+    let dispatch_tx = tx.clone();
     let mut app = wayapp::Application::new(move |t| {
-        let _ = tx.send(AppEvent::WaylandDispatch(t));
+        let _ = dispatch_tx.send(AppEvent::WaylandDispatch(t));
     });
 
-    // Example layer surface
-    let layer_surface = app.layer_shell.create_layer_surface(
-        &app.qh,
-        app.compositor_state.create_surface(&app.qh),
-        smithay_client_toolkit::shell::wlr_layer::Layer::Top,
-        Some(APP_ID),
-        None,
-    );
-    layer_surface.set_keyboard_interactivity(
-        smithay_client_toolkit::shell::wlr_layer::KeyboardInteractivity::Exclusive,
+    let reload_tx = tx.clone();
+    if let Err(error) = config_watcher::watch(move || {
+        let _ = reload_tx.send(AppEvent::ReloadSettings);
+    }) {
+        log::warn!("Settings are not reloaded on changes: {:?}", error);
+    }
+
+    let plugin_message_tx = tx.clone();
+    let mut my_app = Centerpiece::new(
+        mode,
+        std::sync::Arc::new(move || {
+            let _ = plugin_message_tx.send(AppEvent::PluginMessage);
+        }),
     );
-    layer_surface.set_margin(0, 0, 0, 0);
-    layer_surface.set_size(800, 600);
-    layer_surface.commit();
 
-    let mut my_app = Centerpiece::new(&layer_surface);
-    let mut egui_surface = wayapp::EguiSurfaceState::new(&app, &layer_surface, 800, 600);
+    if mode == Mode::Daemon {
+        control::listen(move |command| {
+            let _ = tx.send(AppEvent::Control(command));
+        });
+    }
+
+    let mut egui_surface: Option<window::Surface> = None;
+    // The window is created once the outputs are known to open it on the configured output,
+    // in daemon mode only once a show command arrives
+    let mut show_pending = mode != Mode::Daemon;
 
     // Run the Wayland event loop
     app.run_dispatcher();
@@ -53,173 +134,426 @@ pub fn main() {
             match event {
                 AppEvent::WaylandDispatch(token) => {
                     let events = app.dispatch_pending(token);
-                    egui_surface.handle_events(&mut app, &events, &mut |ctx| my_app.update(ctx));
-                } // Handle other events here
+                    if show_pending {
+                        show_pending = false;
+                        egui_surface = Some(window::create_surface(&app));
+                        let _ = app.conn.flush();
+                    }
+                    if let Some(surface) = egui_surface.as_mut() {
+                        window::handle_events(&app, surface, &events);
+                        my_app.handle_scroll_events(&events);
+                        surface.handle_events(&mut app, &events, &mut |ctx| my_app.update(ctx));
+                    }
+                    if my_app.take_hide_request() {
+                        egui_surface = None;
+                        my_app.reset();
+                    }
+                }
+                AppEvent::ReloadSettings => {
+                    let previous = settings::Settings::get_or_init();
+                    if let Err(error) = settings::Settings::reload() {
+                        log::error!("Keeping the previous settings: {:#}", error);
+                        continue;
+                    }
+                    log::info!("Reloaded settings");
+                    my_app.apply_settings(&previous);
+
+                    // Layer and output of a surface are fixed, changed window settings need a new one
+                    if egui_surface.is_some()
+                        && settings::Settings::get_or_init().window != previous.window
+                    {
+                        egui_surface = Some(window::create_surface(&app));
+                    } else if let Some(surface) = egui_surface.as_mut() {
+                        surface.request_frame();
+                    }
+                    let _ = app.conn.flush();
+                }
+                AppEvent::PluginMessage => {
+                    // A hidden daemon handles the messages once its window opens
+                    if let Some(surface) = egui_surface.as_mut() {
+                        surface.request_frame();
+                        let _ = app.conn.flush();
+                    }
+                }
+                AppEvent::Control(command) => {
+                    let show = match command {
+                        settings::cli::ControlCommand::Show => true,
+                        settings::cli::ControlCommand::Hide => false,
+                        settings::cli::ControlCommand::Toggle => egui_surface.is_none(),
+                    };
+                    if show && egui_surface.is_none() {
+                        my_app.refresh_plugins();
+                        my_app.drain_messages();
+                        egui_surface = Some(window::create_surface(&app));
+                        let _ = app.conn.flush();
+                    } else if !show && egui_surface.is_some() {
+                        egui_surface = None;
+                        my_app.reset();
+                        let _ = app.conn.flush();
+                    }
+                }
             }
         }
     }
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    #[default]
+    Standalone,
+    Daemon,
+    Dmenu {
+        print_query: bool,
+    },
+    Headless,
+}
+
 enum AppEvent {
     WaylandDispatch(wayapp::DispatchToken),
+    Control(settings::cli::ControlCommand),
+    /// The config file changed
+    ReloadSettings,
+    /// A plugin sent messages, they are handled on the next frame
+    PluginMessage,
     // Other events can be added here
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     RegisterPlugin(model::Plugin),
-    UpdateEntries(String, Vec<model::Entry>),
+    /// Entries matching the search of the given generation.
+    UpdateEntries(String, u64, Vec<model::Entry>),
+    /// Sets or clears the failure shown for a plugin.
+    UpdateStatus(String, Option<String>),
     Exit,
 }
 
 #[derive(Default)]
 struct Centerpiece {
     query: String,
+    /// Increases with every change of the query
+    search_generation: u64,
     active_entry_index: usize,
     plugins: Vec<model::Plugin>,
-    plugin_channels: Vec<async_channel::Receiver<Message>>,
+    /// Channels of the running plugin instances by instance id
+    plugin_channels: std::collections::BTreeMap<String, async_channel::Receiver<Message>>,
+    /// Config sections the running plugin instances were started with by instance id
+    plugin_settings: std::collections::HashMap<String, serde_yaml::Value>,
+    mode: Mode,
+    hide_requested: bool,
+    plugin_filter: Option<String>,
+    /// Whether plugins ignore the result limits of their instance, e.g. to activate any entry
+    ignore_result_limits: bool,
+    /// Index of the selected action while the action menu of the active entry is open
+    active_action_index: Option<usize>,
+    /// The running plugin instances by instance id, their keywords scope a search to them
+    plugin_instances: std::collections::HashMap<String, plugin::registry::PluginInstance>,
+    /// Scrolled distance in entries which did not move the selection yet
+    scroll_remainder: f32,
+    /// Number of entries shown in the window when it was last drawn
+    page_size: usize,
+    icons: icon::Icons,
+    /// Colors and sizes resolved from the settings, resolved again when the settings change
+    theme: theme::Theme,
+    /// Fonts built from the theme settings on the first frame
+    font_definitions: Option<egui::FontDefinitions>,
+    /// Called whenever a plugin sent a message, wakes up the event loop of the window
+    on_plugin_message: Option<std::sync::Arc<dyn Fn() + Send + Sync>>,
 }
 
 impl Centerpiece {
-    fn new(_cc: &smithay_client_toolkit::shell::wlr_layer::LayerSurface) -> Self {
-        let mut centerpiece = Self::default();
-        println!("creating centerpiece");
+    fn new(mode: Mode, on_plugin_message: std::sync::Arc<dyn Fn() + Send + Sync>) -> Self {
+        let mut centerpiece = Self {
+            mode,
+            on_plugin_message: Some(on_plugin_message),
+            theme: theme::Theme::new(&settings::Settings::get_or_init()),
+            ..Default::default()
+        };
+        log::info!("creating centerpiece");
         centerpiece.launch_plugins();
         centerpiece
     }
 
     fn launch_plugins(self: &mut Centerpiece) {
-        let settings = settings::Settings::get_or_init();
-
-        if settings.plugin.applications.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::applications::ApplicationsPlugin,
-            >());
+        if matches!(self.mode, Mode::Dmenu { .. }) {
+            let instance = plugin::registry::PluginInstance::of::<plugin::dmenu::DmenuPlugin>();
+            self.plugin_instances
+                .insert(instance.id.clone(), instance.clone());
+            let plugin_channel = plugin::utils::spawn(
+                instance.clone(),
+                <plugin::dmenu::DmenuPlugin as plugin::utils::Plugin>::new,
+            );
+            self.add_plugin_channel(instance.id, plugin_channel);
+            return;
         }
 
-        if settings.plugin.brave_bookmarks.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::brave::bookmarks::BookmarksPlugin,
-            >());
+        let settings = settings::Settings::get_or_init();
+        for configured_instance in plugin::registry::instances(&settings) {
+            self.spawn_plugin(configured_instance);
         }
+    }
 
-        if settings.plugin.brave_progressive_web_apps.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::brave::progressive_web_apps::ProgressiveWebAppsPlugin,
-            >());
+    fn spawn_plugin(&mut self, mut configured_instance: plugin::registry::ConfiguredInstance) {
+        if self.ignore_result_limits {
+            configured_instance.instance = configured_instance.instance.without_result_limits();
         }
-
-        if settings.plugin.brave_history.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::brave::history::HistoryPlugin,
-            >());
+        let instance = &configured_instance.instance;
+        if let Some(plugin_filter) = &self.plugin_filter
+            && plugin_filter != &instance.id
+        {
+            return;
         }
 
-        if settings.plugin.clock.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::clock::ClockPlugin,
-            >());
-        }
+        self.plugin_instances
+            .insert(instance.id.clone(), instance.clone());
 
-        if settings.plugin.firefox_bookmarks.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::firefox::bookmarks::BookmarksPlugin,
-            >());
+        let instance_id = instance.id.clone();
+        let instance_settings = configured_instance.settings().clone();
+        match configured_instance.spawn() {
+            Ok(plugin_channel) => {
+                self.add_plugin_channel(instance_id.clone(), plugin_channel);
+                self.plugin_settings.insert(instance_id, instance_settings);
+            }
+            Err(error) => log::error!(
+                "Failed to start plugin instance '{}': {:?}",
+                instance_id,
+                error
+            ),
         }
+    }
 
-        if settings.plugin.firefox_history.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::firefox::history::HistoryPlugin,
-            >());
-        }
+    fn add_plugin_channel(
+        &mut self,
+        instance_id: String,
+        plugin_channel: async_channel::Receiver<Message>,
+    ) {
+        let plugin_channel = match &self.on_plugin_message {
+            Some(on_plugin_message) => {
+                plugin::utils::notify_on_message(plugin_channel, on_plugin_message.clone())
+            }
+            None => plugin_channel,
+        };
+        self.plugin_channels.insert(instance_id, plugin_channel);
+    }
 
-        if settings.plugin.git_repositories.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::git_repositories::GitRepositoriesPlugin,
-            >());
+    /// Stops a plugin instance, its thread ends once the channel for its requests is closed.
+    fn stop_plugin(&mut self, instance_id: &str) {
+        // Unhandled messages may hold a sender of the channel for requests
+        if let Some(plugin_channel) = self.plugin_channels.remove(instance_id) {
+            while plugin_channel.try_recv().is_ok() {}
         }
+        self.plugin_settings.remove(instance_id);
+        self.plugin_instances.remove(instance_id);
+        self.plugins.retain(|plugin| plugin.id != instance_id);
+    }
 
-        if settings.plugin.gitmoji.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::gitmoji::GitmojiPlugin,
-            >());
+    /// Applies reloaded settings.
+    ///
+    /// Layout and keybindings are read on every frame, the theme is resolved again. Plugin
+    /// instances are started, stopped, or restarted if their config section changed.
+    fn apply_settings(&mut self, previous: &settings::Settings) {
+        let settings = settings::Settings::get_or_init();
+        if settings.theme.font != previous.theme.font {
+            self.font_definitions = None;
         }
-
-        if settings.plugin.resource_monitor_battery.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::resource_monitor::battery::BatteryPlugin,
-            >());
+        self.theme = theme::Theme::new(&settings);
+        // Icons are rendered in the size of the font
+        if settings.icon != previous.icon || settings.theme.font.size != previous.theme.font.size {
+            self.icons = icon::Icons::default();
         }
 
-        if settings.plugin.resource_monitor_cpu.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::resource_monitor::cpu::CpuPlugin,
-            >());
+        if matches!(self.mode, Mode::Dmenu { .. }) {
+            return;
         }
-
-        if settings.plugin.resource_monitor_disks.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::resource_monitor::disks::DisksPlugin,
-            >());
+        let instances = plugin::registry::instances(&settings);
+        let stopped_instance_ids: Vec<String> = self
+            .plugin_settings
+            .iter()
+            .filter(|(instance_id, instance_settings)| {
+                !instances.iter().any(|configured_instance| {
+                    configured_instance.instance.id == **instance_id
+                        && configured_instance.settings() == *instance_settings
+                })
+            })
+            .map(|(instance_id, _)| instance_id.clone())
+            .collect();
+        for instance_id in stopped_instance_ids {
+            log::info!("Stopping plugin instance '{}'", instance_id);
+            self.stop_plugin(&instance_id);
         }
-
-        if settings.plugin.resource_monitor_memory.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::resource_monitor::memory::MemoryPlugin,
-            >());
+        for configured_instance in instances {
+            if !self
+                .plugin_channels
+                .contains_key(&configured_instance.instance.id)
+            {
+                self.spawn_plugin(configured_instance);
+            }
         }
 
-        if settings.plugin.system.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::system::SystemPlugin,
-            >());
-        }
+        self.close_action_menu();
+        self.select_first_entry();
+    }
 
-        if settings.plugin.wifi.enable {
-            self.plugin_channels
-                .push(crate::plugin::utils::spawn::<crate::plugin::wifi::WifiPlugin>());
+    fn exit(&mut self) {
+        match self.mode {
+            Mode::Daemon => {
+                self.hide_requested = true;
+                return;
+            }
+            Mode::Standalone => lock::LockFile::cleanup(),
+            // The lock file belongs to another instance in dmenu and headless mode
+            Mode::Dmenu { .. } | Mode::Headless => {}
         }
+        std::process::exit(0);
+    }
 
-        if settings.plugin.niri_windows.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::niri_windows::NiriWindowsPlugin,
-            >());
+    /// Closes the window without activating an entry.
+    fn cancel(&mut self) {
+        if matches!(self.mode, Mode::Dmenu { .. }) {
+            // dmenu signals an aborted selection through a non-zero exit code
+            std::process::exit(1);
         }
+        self.exit();
+    }
 
-        if settings.plugin.sway_windows.enable {
-            self.plugin_channels.push(crate::plugin::utils::spawn::<
-                crate::plugin::sway_windows::SwayWindowsPlugin,
-            >());
-        }
+    fn take_hide_request(&mut self) -> bool {
+        std::mem::take(&mut self.hide_requested)
     }
 
-    fn exit() -> ! {
-        lock::LockFile::cleanup();
-        std::process::exit(0);
+    /// Clears the query, so a daemon shows a fresh search the next time its window opens.
+    fn reset(&mut self) {
+        self.query.clear();
+        self.search();
     }
 
+    /// Runs the commands bound to the pressed keys.
+    ///
+    /// Bound keys are removed from the input, so the query input does not handle them again,
+    /// e.g. deleting another word on ctrl+w.
     fn handle_input(&mut self, ctx: &egui::Context) {
-        if ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
-            self.activate_selected_entry();
-        }
-        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
-            Self::exit();
-        }
-        if ctx.input(|i| {
-            (i.modifiers.ctrl && i.key_pressed(egui::Key::K)) || i.key_pressed(egui::Key::ArrowUp)
-        }) {
-            self.select_previous_entry();
+        let keybindings = &settings::Settings::get_or_init().keybindings;
+        let mut commands: Vec<KeyCommand> = vec![];
+        ctx.input_mut(|input| {
+            input.events.retain(|event| {
+                let egui::Event::Key {
+                    key,
+                    pressed,
+                    modifiers,
+                    ..
+                } = event
+                else {
+                    return true;
+                };
+                let Some(command) = keybindings.command(*key, *modifiers) else {
+                    return true;
+                };
+                if *pressed {
+                    commands.push(command);
+                }
+                false
+            })
+        });
+
+        for command in commands {
+            if self.active_action_index.is_some() {
+                self.run_action_menu_command(command);
+            } else {
+                self.run_command(command);
+            }
         }
-        if ctx.input(|i| {
-            (i.modifiers.ctrl && i.key_pressed(egui::Key::J)) || i.key_pressed(egui::Key::ArrowDown)
-        }) {
-            self.select_next_entry();
+    }
+
+    fn run_command(&mut self, command: KeyCommand) {
+        match command {
+            KeyCommand::SelectNext => self.select_next_entry(),
+            KeyCommand::SelectPrevious => self.select_previous_entry(),
+            KeyCommand::SelectNextPlugin => self.select_next_plugin(),
+            KeyCommand::SelectPreviousPlugin => self.select_previous_plugin(),
+            KeyCommand::SelectFirst => self.select_first_entry(),
+            KeyCommand::SelectLast => self.select_last_entry(),
+            KeyCommand::PageUp => self.select_previous_page(),
+            KeyCommand::PageDown => self.select_next_page(),
+            KeyCommand::Activate => self.activate(),
+            KeyCommand::OpenActions => self.open_action_menu(),
+            KeyCommand::Close => self.cancel(),
+            KeyCommand::ClearQuery => self.clear_query(),
+            KeyCommand::DeleteWord => self.delete_word(),
+            KeyCommand::Unbound => {}
         }
-        if ctx.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::P)) {
-            self.select_previous_plugin();
+    }
+
+    fn run_action_menu_command(&mut self, command: KeyCommand) {
+        match command {
+            KeyCommand::SelectNext => self.select_next_action(),
+            KeyCommand::SelectPrevious => self.select_previous_action(),
+            KeyCommand::SelectFirst | KeyCommand::PageUp => self.active_action_index = Some(0),
+            KeyCommand::SelectLast | KeyCommand::PageDown => {
+                let action_count = self.active_entry_action_titles().len();
+                self.active_action_index = Some(action_count.saturating_sub(1));
+            }
+            KeyCommand::Activate => self.activate_selected_action(),
+            KeyCommand::OpenActions | KeyCommand::Close => self.close_action_menu(),
+            // Editing the query would change the entry the menu belongs to
+            KeyCommand::SelectNextPlugin
+            | KeyCommand::SelectPreviousPlugin
+            | KeyCommand::ClearQuery
+            | KeyCommand::DeleteWord
+            | KeyCommand::Unbound => {}
         }
-        if ctx.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::N)) {
-            self.select_next_plugin();
+    }
+
+    fn clear_query(&mut self) {
+        self.query.clear();
+        self.search();
+    }
+
+    /// Removes the last word of the query and the whitespace behind it.
+    fn delete_word(&mut self) {
+        let query = self.query.trim_end();
+        let word_start = query
+            .rfind(char::is_whitespace)
+            .map(|index| index + 1)
+            .unwrap_or(0);
+        self.query.truncate(word_start);
+        self.search();
+    }
+
+    /// Moves the selection by one row per wheel step or per entry height scrolled on a touchpad.
+    ///
+    /// Scrolling is read from the wayland events, which report touchpad scrolling in pixels.
+    fn handle_scroll_events(&mut self, events: &[wayapp::WaylandEvent]) {
+        for event in events {
+            let wayapp::WaylandEvent::PointerEvent((_, _, PointerEventKind::Axis { vertical, .. })) =
+                event
+            else {
+                continue;
+            };
+
+            self.scroll_remainder += if vertical.value120 != 0 {
+                vertical.value120 as f32 / 120.
+            } else if vertical.discrete != 0 {
+                vertical.discrete as f32
+            } else {
+                vertical.absolute as f32 / self.theme.entry_height(false)
+            };
+            if vertical.stop {
+                self.scroll_remainder = 0.;
+            }
+
+            while self.scroll_remainder >= 1. {
+                self.scroll_remainder -= 1.;
+                match self.active_action_index {
+                    Some(_) => self.select_next_action(),
+                    None => self.select_next_entry(),
+                }
+            }
+            while self.scroll_remainder <= -1. {
+                self.scroll_remainder += 1.;
+                match self.active_action_index {
+                    Some(_) => self.select_previous_action(),
+                    None => self.select_previous_entry(),
+                }
+            }
         }
     }
 
@@ -228,47 +562,273 @@ impl Centerpiece {
             match message {
                 Message::RegisterPlugin(plugin) => self.register_plugin(plugin),
 
-                Message::UpdateEntries(plugin_id, entries) => {
-                    self.update_entries(plugin_id, entries)
+                Message::UpdateEntries(plugin_id, generation, entries) => {
+                    self.update_entries(plugin_id, generation, entries)
                 }
 
+                Message::UpdateStatus(plugin_id, status) => self.update_status(plugin_id, status),
+
                 Message::Exit => {
-                    Self::exit();
+                    self.exit();
                 }
             }
         }
     }
 
-    fn entries(&self) -> Vec<&model::Entry> {
-        self.plugins
+    /// Asks the plugins whose entries go stale to update them, e.g. to add the windows opened
+    /// while a daemon was hidden. Other plugins keep their entries to open the window quickly.
+    fn refresh_plugins(&self) {
+        for plugin in self.plugins.iter().filter(|plugin| plugin.update_on_show) {
+            // A busy plugin keeps its entries, showing the window must not wait for it
+            let _ = plugin
+                .app_channel_out
+                .try_send(model::PluginRequest::Timeout);
+        }
+    }
+
+    /// Handles every message the plugins sent while the daemon window was hidden.
+    fn drain_messages(&mut self) {
+        self.handle_pending_messages();
+        // The window is about to be shown, a late exit request must not hide it again
+        self.hide_requested = false;
+    }
+
+    /// Handles every message the plugins sent so far.
+    ///
+    /// The selected entry stays selected while the entries change. Only the first entry is
+    /// not kept, so the best match is selected while the results of a search arrive.
+    fn handle_pending_messages(&mut self) {
+        let messages: Vec<Message> = self
+            .plugin_channels
+            .values()
+            .flat_map(|plugin_channel| std::iter::from_fn(|| plugin_channel.try_recv().ok()))
+            .collect();
+        if messages.is_empty() {
+            return;
+        }
+
+        let keep_selection = self.active_entry_index > 0 || self.active_action_index.is_some();
+        let selected_entry = keep_selection.then(|| self.selected_entry_key()).flatten();
+        self.handle_messages(messages);
+        self.select_entry(selected_entry);
+    }
+
+    fn selected_entry_key(&self) -> Option<model::EntryKey> {
+        let entries = self.entries_with_plugin();
+        let (plugin, entry) = entries.get(self.active_entry_index)?;
+        Some(model::EntryKey::new(plugin, entry))
+    }
+
+    /// Selects the entry with the key, or the closest entry to the selection if it is gone.
+    fn select_entry(&mut self, entry_key: Option<model::EntryKey>) {
+        let entries = self.entries_with_plugin();
+        let entry_count = entries.len();
+        let index = entry_key.as_ref().and_then(|entry_key| {
+            entries.iter().position(|(plugin, entry)| {
+                plugin.id == entry_key.plugin_id && entry.id == entry_key.entry_id
+            })
+        });
+
+        match index {
+            Some(index) => self.active_entry_index = index,
+            None => {
+                // The action menu belonged to the entry which is gone
+                if entry_key.is_some() {
+                    self.close_action_menu();
+                }
+                self.active_entry_index =
+                    self.active_entry_index.min(entry_count.saturating_sub(1));
+            }
+        }
+    }
+
+    /// All entries in display order together with the plugin they belong to.
+    fn entries_with_plugin(&self) -> Vec<(&model::Plugin, &model::Entry)> {
+        let mut entries: Vec<(&model::Plugin, &model::Entry)> = self
+            .plugins
             .iter()
-            .flat_map(|plugin| &plugin.entries)
+            // Plugins outside of the scope of the query keep their entries of earlier searches
+            .filter(|plugin| self.plugin_query(&plugin.id).is_some())
+            .flat_map(|plugin| plugin.entries.iter().map(move |entry| (plugin, entry)))
+            .collect();
+
+        let settings = settings::Settings::get_or_init();
+        if settings.layout.mode == settings::LayoutMode::Flat {
+            // Stable sort, entries with equal scores stay ordered by plugin priority
+            entries.sort_by_key(|(plugin, entry)| {
+                let weight = settings.layout.plugin_weights.get(&plugin.id);
+                std::cmp::Reverse(i64::from(entry.score) + i64::from(*weight.unwrap_or(&0)))
+            });
+        }
+
+        entries
+    }
+
+    fn entries(&self) -> Vec<&model::Entry> {
+        self.entries_with_plugin()
+            .into_iter()
+            .map(|(_, entry)| entry)
             .collect()
     }
 
-    fn active_entry_id(&self) -> Option<&String> {
-        let entries = self.entries();
-        let active_entry = entries.get(self.active_entry_index);
-        match active_entry {
-            Some(entry) => Some(&entry.id),
-            None => None,
-        }
+    /// Whether the entry at the index is the first of a consecutive run of entries from one plugin.
+    fn is_plugin_start(entries: &[(&model::Plugin, &model::Entry)], index: usize) -> bool {
+        index == 0 || entries[index - 1].0.id != entries[index].0.id
+    }
+
+    /// The keyword at the start of the query which scopes the search to some plugins,
+    /// followed by the rest of the query.
+    fn scope(&self) -> Option<(&str, &str)> {
+        let (keyword, query) = self.query.split_once(' ')?;
+        self.plugin_instances
+            .values()
+            .any(|instance| instance.keywords.iter().any(|k| k == keyword))
+            .then_some((keyword, query))
+    }
+
+    /// The query a plugin should search for, or `None` if the plugin shows no entries for the
+    /// query, e.g. if the search is scoped to other plugins or the query is too short.
+    fn plugin_query(&self, plugin_id: &str) -> Option<String> {
+        let instance = self.plugin_instances.get(plugin_id)?;
+        let query = match self.scope() {
+            Some((keyword, query)) if instance.keywords.iter().any(|k| k == keyword) => query,
+            Some(_) => return None,
+            None if instance.only_when_scoped => return None,
+            None => self.query.as_str(),
+        };
+
+        (query.chars().count() >= instance.min_query_length).then(|| String::from(query))
+    }
+
+    /// Titles of the plugins the search is scoped to, if the query starts with a keyword.
+    fn scope_titles(&self) -> Option<String> {
+        self.scope()?;
+        let titles: Vec<&str> = self
+            .plugins
+            .iter()
+            .filter(|plugin| self.plugin_query(&plugin.id).is_some())
+            .map(|plugin| plugin.title.as_str())
+            .collect();
+        Some(titles.join(", "))
     }
 
     fn search(&mut self) {
-        for plugin in self.plugins.iter_mut() {
-            let _ = plugin
+        self.search_generation += 1;
+        self.send_searches();
+
+        self.close_action_menu();
+        self.select_first_entry();
+    }
+
+    /// Sends the current search to every plugin which did not get it yet.
+    ///
+    /// Plugins with a full channel get the search on a later frame instead of blocking the
+    /// window, the plugin only searches the latest query it receives.
+    fn send_searches(&mut self) {
+        let generation = self.search_generation;
+        for index in 0..self.plugins.len() {
+            if self.plugins[index].generation == generation {
+                continue;
+            }
+            let query = self.plugin_query(&self.plugins[index].id);
+            let plugin = &mut self.plugins[index];
+            // Plugins outside of the scope of the query are not searched
+            let Some(query) = query else {
+                plugin.generation = generation;
+                continue;
+            };
+
+            let search = model::Search { generation, query };
+            if let Err(async_channel::TrySendError::Full(_)) = plugin
                 .app_channel_out
-                .send_blocking(crate::model::PluginRequest::Search(self.query.clone()));
+                .try_send(model::PluginRequest::Search(search))
+            {
+                continue;
+            }
+            plugin.generation = generation;
         }
+    }
 
-        self.select_first_entry();
+    /// Titles of the default action followed by the additional actions of the active entry.
+    fn active_entry_action_titles(&self) -> Vec<String> {
+        let entries = self.entries();
+        let Some(entry) = entries.get(self.active_entry_index) else {
+            return vec![];
+        };
+
+        let default_action_title = if entry.action.is_empty() {
+            String::from("activate")
+        } else {
+            entry.action.clone()
+        };
+        std::iter::once(default_action_title)
+            .chain(entry.actions.iter().map(|action| action.title.clone()))
+            .collect()
+    }
+
+    fn open_action_menu(&mut self) {
+        // Entries without additional actions only offer their default action
+        if self.active_entry_action_titles().len() > 1 {
+            self.active_action_index = Some(0);
+        }
+    }
+
+    fn close_action_menu(&mut self) {
+        self.active_action_index = None;
+    }
+
+    fn select_previous_action(&mut self) {
+        let action_count = self.active_entry_action_titles().len();
+        if let Some(index) = self.active_action_index.as_mut()
+            && action_count > 0
+        {
+            *index = (*index + action_count - 1) % action_count;
+        }
+    }
+
+    fn select_next_action(&mut self) {
+        let action_count = self.active_entry_action_titles().len();
+        if let Some(index) = self.active_action_index.as_mut()
+            && action_count > 0
+        {
+            *index = (*index + 1) % action_count;
+        }
+    }
+
+    fn activate_selected_action(&mut self) {
+        let Some(action_index) = self.active_action_index.take() else {
+            return;
+        };
+
+        // The first action of the menu is the default action of the entry
+        let action_id = action_index.checked_sub(1).and_then(|index| {
+            let entries = self.entries();
+            let entry = entries.get(self.active_entry_index)?;
+            Some(entry.actions.get(index)?.id.clone())
+        });
+        self.activate_selected_entry(action_id);
     }
 
     fn select_first_entry(&mut self) {
         self.active_entry_index = 0;
     }
 
+    fn select_last_entry(&mut self) {
+        self.active_entry_index = self.entries().len().saturating_sub(1);
+    }
+
+    /// Moves the selection up by the number of entries which fit into the window.
+    fn select_previous_page(&mut self) {
+        self.active_entry_index = self
+            .active_entry_index
+            .saturating_sub(self.page_size.max(1));
+    }
+
+    fn select_next_page(&mut self) {
+        let last_index = self.entries().len().saturating_sub(1);
+        self.active_entry_index = (self.active_entry_index + self.page_size.max(1)).min(last_index);
+    }
+
     fn select_previous_entry(&mut self) {
         let entries = self.entries();
         if entries.is_empty() {
@@ -293,19 +853,10 @@ impl Centerpiece {
     }
 
     fn select_next_plugin(&mut self) {
-        let accumulated_entries = self
-            .plugins
-            .iter()
-            .map(|plugin| plugin.entries.len())
-            .scan(0, |acc, len| {
-                let prev = *acc;
-                *acc += len;
-                Some(prev)
-            })
-            .find(|&total| total > self.active_entry_index)
+        let entries = self.entries_with_plugin();
+        self.active_entry_index = (self.active_entry_index + 1..entries.len())
+            .find(|&index| Self::is_plugin_start(&entries, index))
             .unwrap_or(self.active_entry_index);
-
-        self.active_entry_index = accumulated_entries;
     }
 
     fn select_previous_plugin(&mut self) {
@@ -313,39 +864,50 @@ impl Centerpiece {
             return self.select_first_entry();
         }
 
-        let accumulated_entries = self
-            .plugins
-            .iter()
-            .map(|plugin| plugin.entries.len())
-            .scan(0, |acc, len| {
-                let prev = *acc;
-                *acc += len;
-                Some(prev)
-            })
-            .take_while(|&total| total < self.active_entry_index)
-            .last()
+        let entries = self.entries_with_plugin();
+        self.active_entry_index = (0..self.active_entry_index.min(entries.len()))
+            .rev()
+            .find(|&index| Self::is_plugin_start(&entries, index))
             .unwrap_or(0);
-
-        self.active_entry_index = accumulated_entries;
     }
 
-    fn register_plugin(&mut self, plugin: crate::model::Plugin) {
-        drop(
-            plugin
+    fn register_plugin(&mut self, mut plugin: crate::model::Plugin) {
+        if let Some(query) = self.plugin_query(&plugin.id) {
+            let search = model::Search {
+                generation: self.search_generation,
+                query,
+            };
+            let _ = plugin
                 .app_channel_out
-                .send(crate::model::PluginRequest::Search(self.query.clone())),
-        );
+                .try_send(crate::model::PluginRequest::Search(search));
+        }
+        plugin.generation = self.search_generation;
         self.plugins.push(plugin);
-        self.plugins.sort_by(|a, b| b.priority.cmp(&a.priority));
+        self.plugins
+            .sort_by_key(|plugin| std::cmp::Reverse(plugin.priority));
     }
 
-    fn update_entries(&mut self, plugin_id: String, entries: Vec<crate::model::Entry>) {
+    fn update_entries(
+        &mut self,
+        plugin_id: String,
+        generation: u64,
+        mut entries: Vec<crate::model::Entry>,
+    ) {
+        // Entries arrive ordered by how well they match, the best ones are kept
+        if let Some(max_results) = self
+            .plugin_instances
+            .get(&plugin_id)
+            .and_then(|instance| instance.max_results)
+        {
+            entries.truncate(max_results);
+        }
+
         let plugin = self
             .plugins
             .iter_mut()
             .find(|plugin| plugin.id == plugin_id);
         if plugin.is_none() {
-            println!(
+            log::warn!(
                 "Appending entry failed. Could not find plugin with id {:?}",
                 plugin_id
             );
@@ -353,112 +915,180 @@ impl Centerpiece {
         }
 
         let plugin = plugin.unwrap();
+        // Entries of an earlier query must not replace the entries of the current query
+        if generation < plugin.generation {
+            return;
+        }
         plugin.entries = entries;
     }
 
-    fn activate_selected_entry(&mut self) -> Option<()> {
-        let active_entry_id = self.active_entry_id()?.clone();
+    fn update_status(&mut self, plugin_id: String, status: Option<String>) {
+        let Some(plugin) = self
+            .plugins
+            .iter_mut()
+            .find(|plugin| plugin.id == plugin_id)
+        else {
+            log::warn!(
+                "Updating status failed. Could not find plugin with id {:?}",
+                plugin_id
+            );
+            return;
+        };
 
-        let entry = self
-            .entries()
-            .into_iter()
-            .find(|entry| entry.id == *active_entry_id)?
-            .clone();
+        plugin.status = status;
+    }
+
+    /// Plugins in the scope of the query which reported a failure.
+    fn plugins_with_status(&self) -> Vec<&model::Plugin> {
+        self.plugins
+            .iter()
+            .filter(|plugin| plugin.status.is_some() && self.plugin_query(&plugin.id).is_some())
+            .collect()
+    }
+
+    fn activate(&mut self) {
+        if let Mode::Dmenu { print_query } = self.mode
+            && (print_query || self.entries().is_empty())
+        {
+            println!("{}", self.query);
+            return self.exit();
+        }
 
-        let plugin = self.plugins.iter_mut().find(|plugin| {
-            plugin
-                .entries
-                .iter()
-                .any(|entry| entry.id == *active_entry_id)
-        })?;
+        self.activate_selected_entry(None);
+    }
+
+    fn activate_selected_entry(&mut self, action_id: Option<String>) -> Option<()> {
+        let entries = self.entries_with_plugin();
+        let (plugin, entry) = entries.get(self.active_entry_index)?;
+
+        // Items piped into dmenu mode have no stable identity
+        if !matches!(self.mode, Mode::Dmenu { .. }) {
+            frecency::record(&model::EntryKey::new(plugin, entry));
+        }
 
-        plugin
+        let activate_result = plugin
             .app_channel_out
-            .send_blocking(model::PluginRequest::Activate(entry))
-            .ok()
+            .try_send(model::PluginRequest::Activate(
+                Box::new((*entry).clone()),
+                action_id,
+            ));
+        // Waiting for the plugin would freeze the window
+        if let Err(async_channel::TrySendError::Full(_)) = &activate_result {
+            log::warn!(
+                "Plugin instance '{}' is busy, the entry was not activated.",
+                plugin.id
+            );
+        }
+        activate_result.ok()
     }
 
     fn set_fonts(&mut self, ctx: &egui::Context) {
-        let mut fonts = egui::FontDefinitions::default();
-        fonts.font_data.insert(
-            "NerdFontSymbols".to_owned(),
-            std::sync::Arc::new(egui::FontData::from_static(include_bytes!(
-                "../assets/SymbolsNerdFontMono-Regular.ttf"
-            ))),
-        );
-        fonts
-            .families
-            .entry(egui::FontFamily::Monospace)
-            .or_default()
-            .push("NerdFontSymbols".to_owned());
-        ctx.set_fonts(fonts);
+        // The configured font is read once, egui skips unchanged font definitions
+        let fonts = self
+            .font_definitions
+            .get_or_insert_with(theme::font_definitions);
+        ctx.set_fonts(fonts.clone());
     }
 
     fn set_theme(&mut self, ctx: &egui::Context) {
         let text_styles: std::collections::BTreeMap<_, _> = [
             (
                 egui::TextStyle::Heading,
-                egui::FontId::new(0.75 * crate::REM, egui::FontFamily::Monospace),
+                egui::FontId::new(0.75 * crate::theme::rem(), egui::FontFamily::Monospace),
             ),
             (
                 egui::TextStyle::Body,
-                egui::FontId::new(1. * crate::REM, egui::FontFamily::Monospace),
+                egui::FontId::new(1. * crate::theme::rem(), egui::FontFamily::Monospace),
+            ),
+            (
+                egui::TextStyle::Small,
+                egui::FontId::new(0.75 * crate::theme::rem(), egui::FontFamily::Monospace),
             ),
         ]
         .into();
         ctx.all_styles_mut(move |style| style.text_styles = text_styles.clone());
 
-        let settings = settings::Settings::get_or_init();
-        ctx.set_visuals_of(
-            egui::Theme::Dark,
-            egui::Visuals {
-                override_text_color: Some(settings::hexcolor(&settings.color.text)),
-                ..Default::default()
-            },
-        );
+        let theme = &self.theme;
+        let mut visuals = egui::Visuals {
+            override_text_color: Some(theme.text),
+            weak_text_color: theme.muted,
+            ..Default::default()
+        };
+        if let Some(separator) = theme.separator {
+            visuals.widgets.noninteractive.bg_stroke.color = separator;
+        }
+        ctx.set_visuals_of(egui::Theme::Dark, visuals);
     }
 
     fn update(&mut self, ctx: &egui::Context) {
         self.set_fonts(ctx);
         self.set_theme(ctx);
         self.handle_input(ctx);
+        self.icons.update(ctx);
 
-        let mut messages = vec![];
-
-        for plugin_channel in &self.plugin_channels {
-            let message_result = plugin_channel.try_recv();
-            if message_result.is_err() {
-                continue;
-            }
-            messages.push(message_result.unwrap());
-        }
-
-        self.handle_messages(messages);
+        self.handle_pending_messages();
+        self.send_searches();
 
         let settings = settings::Settings::get_or_init();
 
         egui::CentralPanel::default()
             .frame(egui::Frame::new())
             .show(ctx, |ui| {
-                egui::Frame::new()
+                let panel_response = egui::Frame::new()
                     .inner_margin(egui::epaint::MarginF32 {
-                        bottom: 1. * crate::REM,
+                        bottom: 1. * crate::theme::rem(),
                         ..Default::default()
                     })
-                    .corner_radius(0.5 * crate::REM)
-                    .fill(settings::hexcolor(&settings.color.background))
+                    .corner_radius(self.theme.corner_radius)
+                    .fill(self.theme.background)
                     .show(ui, |ui| {
-                        let response = component::query_input::view(ui, &mut self.query);
+                        let scope = self.scope_titles();
+                        let response = component::query_input::view(ui, &mut self.query, scope);
                         response.request_focus();
                         if response.changed() {
                             self.search();
                         }
 
-                        let entries = self.entries();
-                        if !entries.is_empty() {
+                        let entries = self.entries_with_plugin();
+                        let plugins_with_status = self.plugins_with_status();
+                        if !entries.is_empty() || !plugins_with_status.is_empty() {
                             ui.add(Separator::default().spacing(0.));
                         }
 
+                        for plugin in plugins_with_status {
+                            component::plugin_status::view(ui, plugin);
+                        }
+
+                        if let Some(active_action_index) = self.active_action_index
+                            && let Some((_, entry)) = entries.get(self.active_entry_index)
+                        {
+                            component::entry::view(
+                                ui,
+                                entry,
+                                false,
+                                None,
+                                &self.icons,
+                                &self.theme,
+                            );
+                            let clicked_action_index = component::action_menu::view(
+                                ui,
+                                &self.active_entry_action_titles(),
+                                active_action_index,
+                                &self.theme,
+                            );
+                            if let Some(action_index) = clicked_action_index {
+                                self.active_action_index = Some(action_index);
+                                self.activate_selected_action();
+                            }
+                            return;
+                        }
+
+                        // The flat layout shows the plugin as a badge on each entry instead of headers
+                        let grouped = settings.layout.mode == settings::LayoutMode::Grouped;
+                        let badge =
+                            |plugin: &model::Plugin| (!grouped).then_some(plugin.title.clone());
+
+                        let mut clicked_entry = None;
                         let mut divider_added = true;
                         let mut header_added = false;
                         let mut next_entry_index_to_add = self.active_entry_index;
@@ -469,14 +1099,9 @@ impl Centerpiece {
                                 break;
                             }
 
-                            let mut plugin_to_add = None;
-                            let mut last_plugin_start_index = 0;
-                            for plugin in self.plugins.iter() {
-                                if last_plugin_start_index == next_entry_index_to_add {
-                                    plugin_to_add = Some(plugin);
-                                }
-                                last_plugin_start_index += plugin.entries.len();
-                            }
+                            let plugin_to_add = (grouped
+                                && Self::is_plugin_start(&entries, next_entry_index_to_add))
+                            .then_some(entries[next_entry_index_to_add].0);
 
                             if !divider_added && plugin_to_add.is_some() {
                                 ui.separator();
@@ -490,30 +1115,94 @@ impl Centerpiece {
                                 header_added = true;
                                 lines_added += 1;
                                 continue;
-                            } else if lines_added == 0 {
-                                component::entry::view(
+                            } else if lines_added == 0 && next_entry_index_to_add > 0 {
+                                let (plugin, entry) = entries[next_entry_index_to_add - 1];
+                                let response = component::entry::view(
                                     ui,
-                                    entries[next_entry_index_to_add - 1],
+                                    entry,
                                     false,
+                                    badge(plugin),
+                                    &self.icons,
+                                    &self.theme,
                                 );
+                                clicked_entry = clicked_entry
+                                    .or(EntryClick::of(&response, next_entry_index_to_add - 1));
                             }
 
-                            component::entry::view(
+                            let (plugin, entry) = entries[next_entry_index_to_add];
+                            // Rows which don't fit completely are left for the next page
+                            let entry_height = self.theme.entry_height(entry.subtitle.is_some());
+                            if ui.available_height() < entry_height {
+                                break;
+                            }
+                            let response = component::entry::view(
                                 ui,
-                                entries[next_entry_index_to_add],
+                                entry,
                                 next_entry_index_to_add == self.active_entry_index,
+                                badge(plugin),
+                                &self.icons,
+                                &self.theme,
                             );
+                            clicked_entry = clicked_entry
+                                .or(EntryClick::of(&response, next_entry_index_to_add));
                             divider_added = false;
                             header_added = false;
                             next_entry_index_to_add += 1;
                             lines_added += 1;
                         }
-                    });
+                        self.page_size = next_entry_index_to_add - self.active_entry_index;
+
+                        match clicked_entry {
+                            Some(EntryClick::Primary(index)) => {
+                                self.active_entry_index = index;
+                                self.activate();
+                            }
+                            Some(EntryClick::Secondary(index)) => {
+                                self.active_entry_index = index;
+                                self.open_action_menu();
+                            }
+                            None => {}
+                        }
+                    })
+                    .response;
+
+                // The surface is larger than the panel, clicks below the panel dismiss it
+                let clicked_outside = ui.input(|input| {
+                    input.pointer.any_click()
+                        && input
+                            .pointer
+                            .interact_pos()
+                            .is_some_and(|position| !panel_response.rect.contains(position))
+                });
+                if clicked_outside {
+                    if self.active_action_index.is_some() {
+                        self.close_action_menu();
+                    } else {
+                        self.cancel();
+                    }
+                }
             });
     }
 }
 
-impl Centerpiece {}
+/// A click on an entry of the result list.
+enum EntryClick {
+    /// Activates the entry
+    Primary(usize),
+    /// Opens the action menu of the entry
+    Secondary(usize),
+}
 
-pub const REM: f32 = 14.0;
-pub const ENTRY_HEIGHT: f32 = 2.3 * crate::REM;
+impl EntryClick {
+    fn of(response: &egui::Response, index: usize) -> Option<Self> {
+        if response.clicked() {
+            Some(Self::Primary(index))
+        } else if response.secondary_clicked() {
+            Some(Self::Secondary(index))
+        } else {
+            None
+        }
+    }
+}
+
+impl Centerpiece {}
@@ -5,33 +5,99 @@ pub struct Plugin {
     pub title: String,
     pub entries: Vec<Entry>,
     pub app_channel_out: async_channel::Sender<PluginRequest>,
+    /// Failure reported by the plugin, e.g. a missing index file.
+    pub status: Option<String>,
+    /// Generation of the last search sent to the plugin, entries of earlier searches are outdated.
+    pub generation: u64,
+    /// Whether the entries are updated when the window of a daemon is shown.
+    pub update_on_show: bool,
 }
 
-#[derive(Debug, Clone, Ord, PartialOrd)]
+#[derive(Debug, Clone, Default)]
 pub struct Entry {
+    /// Identifies the entry within its plugin instance, see [`EntryKey`]
     pub id: String,
     pub title: String,
+    /// Secondary line shown below the title, e.g. the url of a bookmark.
+    pub subtitle: Option<String>,
     pub action: String,
     pub meta: String,
     pub command: Option<Vec<String>>,
+    /// Additional actions offered in the action menu next to the default `action`.
+    pub actions: Vec<Action>,
+    /// Name of an icon of the icon theme or an absolute path to an image file.
+    pub icon: Option<String>,
+    /// How well the entry matches the current query, set while searching.
+    pub score: u32,
+    /// The characters which matched the current query, set while searching.
+    pub highlight: Highlight,
 }
 
-impl Eq for Entry {}
+#[derive(Debug, Clone, Default, PartialEq, Eq, Ord, PartialOrd)]
+pub enum Highlight {
+    #[default]
+    None,
+    /// Indices of the matched characters of the title
+    Title(Vec<u32>),
+    /// Indices of the matched characters of the meta, e.g. a keyword of an application
+    Meta(Vec<u32>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Ord, PartialOrd)]
+pub struct Action {
+    pub id: String,
+    pub title: String,
+}
+
+/// Identifies an entry across plugins, e.g. the same url is an entry of the bookmarks and of
+/// the history, written as `<plugin>/<entry-id>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntryKey {
+    pub plugin_id: String,
+    pub entry_id: String,
+}
 
-impl PartialEq for Entry {
-    fn eq(&self, other: &Self) -> bool {
-        self.id == other.id
+impl EntryKey {
+    pub fn new(plugin: &Plugin, entry: &Entry) -> Self {
+        Self {
+            plugin_id: plugin.id.clone(),
+            entry_id: entry.id.clone(),
+        }
     }
 }
 
-impl std::hash::Hash for Entry {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.id.hash(state);
+impl std::str::FromStr for EntryKey {
+    type Err = anyhow::Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        // Entry ids may contain slashes, e.g. urls or paths, plugin ids never do
+        let (plugin_id, entry_id) = text
+            .split_once('/')
+            .ok_or_else(|| anyhow::anyhow!("Expected '<plugin>/<entry-id>', got '{}'.", text))?;
+        Ok(Self {
+            plugin_id: String::from(plugin_id),
+            entry_id: String::from(entry_id),
+        })
+    }
+}
+
+impl std::fmt::Display for EntryKey {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{}/{}", self.plugin_id, self.entry_id)
     }
 }
 
+/// A query together with its generation, which increases with every search of the app.
+#[derive(Debug, Clone, Default)]
+pub struct Search {
+    pub generation: u64,
+    pub query: String,
+}
+
+#[derive(Debug)]
 pub enum PluginRequest {
-    Search(String),
+    Search(Search),
     Timeout,
-    Activate(Entry),
+    /// Activates an entry with its default action or with the additional action of the given id.
+    Activate(Box<Entry>, Option<String>),
 }
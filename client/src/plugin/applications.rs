@@ -58,9 +58,14 @@ fn to_entry(
     Some(crate::model::Entry {
         id: desktop_entry.appid.to_string(),
         title,
+        subtitle: desktop_entry
+            .comment(&[locale()])
+            .map(|comment| comment.to_string()),
         action: String::from("open"),
         meta,
         command: Some(cmd),
+        icon: desktop_entry.icon().map(String::from),
+        ..Default::default()
     })
 }
 
@@ -18,6 +18,10 @@ impl Plugin for BookmarksPlugin {
         "󰃃 Bookmarks"
     }
 
+    fn default_keywords() -> &'static [&'static str] {
+        &["b"]
+    }
+
     fn entries(&self) -> Vec<crate::model::Entry> {
         self.entries.clone()
     }
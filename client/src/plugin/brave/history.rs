@@ -18,6 +18,10 @@ impl Plugin for HistoryPlugin {
         "󰋚 History"
     }
 
+    fn default_keywords() -> &'static [&'static str] {
+        &["h"]
+    }
+
     fn entries(&self) -> Vec<crate::model::Entry> {
         self.entries.clone()
     }
@@ -43,16 +47,17 @@ impl Plugin for HistoryPlugin {
         std::fs::copy(history_file_path, &history_cache_file_path)
             .context("Error while creating cache directory")?;
 
-        let connection = sqlite::open(history_cache_file_path).unwrap();
+        let connection = sqlite::open(history_cache_file_path)?;
         let query = "SELECT title, url FROM urls ORDER BY visit_count DESC, last_visit_time DESC";
-        connection.execute(query).unwrap();
+        connection.execute(query)?;
         let url_rows = connection
-            .prepare(query)
-            .unwrap()
+            .prepare(query)?
             .into_iter()
-            .map(|row| row.unwrap());
+            .collect::<Result<Vec<_>, _>>()
+            .context("Error while reading history")?;
 
         self.entries = url_rows
+            .into_iter()
             .map(|row| {
                 let title = row.read::<&str, _>("title");
                 let url = row.read::<&str, _>("url");
@@ -60,9 +65,11 @@ impl Plugin for HistoryPlugin {
                 crate::model::Entry {
                     id: url.to_string(),
                     title: title.to_string(),
+                    subtitle: Some(url.to_string()),
                     action: String::from("open"),
-                    meta: String::from("History"),
+                    meta: format!("History {url}"),
                     command: None,
+                    ..Default::default()
                 }
             })
             .collect();
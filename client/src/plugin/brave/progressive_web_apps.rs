@@ -47,6 +47,16 @@ impl Plugin for ProgressiveWebAppsPlugin {
             .map(|bookmark| bookmark.into())
             .collect();
 
+        // Apps are still listed without icons when the favicons can't be read
+        match crate::plugin::brave::utils::favicons() {
+            Ok(favicons) => {
+                for entry in self.entries.iter_mut() {
+                    entry.icon = favicons.get(&entry.id).cloned();
+                }
+            }
+            Err(error) => log::warn!(target: Self::id(), "{:#}", error),
+        }
+
         self.sort();
         Ok(())
     }
@@ -45,9 +45,11 @@ impl From<&UrlBookmark> for crate::model::Entry {
         crate::model::Entry {
             id: val.url.clone(),
             title: val.name.clone(),
+            subtitle: Some(val.url.clone()),
             action: String::from("open"),
-            meta: String::from("Bookmarks"),
+            meta: format!("Bookmarks {}", val.url),
             command: None,
+            ..Default::default()
         }
     }
 }
@@ -106,3 +108,77 @@ pub fn read_bookmarks_file() -> anyhow::Result<Bookmark> {
 
     Ok(bookmarks_file_content.roots.into())
 }
+
+/// Extracts the largest favicon of each page from the brave favicon database into the cache
+/// directory and returns the paths of the icon files by page url.
+pub fn favicons() -> anyhow::Result<std::collections::HashMap<String, String>> {
+    let config_directory = settings::config_directory()?;
+    let favicons_file_path =
+        format!("{config_directory}/BraveSoftware/Brave-Browser/Default/Favicons");
+
+    let cache_directory = settings::centerpiece_cache_directory()?;
+    let favicons_directory = format!("{cache_directory}/brave-favicons");
+    std::fs::create_dir_all(&favicons_directory)
+        .context("Error while creating favicon cache directory")?;
+    // The database is locked while brave is running
+    let favicons_cache_file_path = format!("{cache_directory}/brave-favicons.sqlite");
+    std::fs::copy(favicons_file_path, &favicons_cache_file_path)
+        .context("Error while copying brave favicon database")?;
+
+    let connection = sqlite::open(favicons_cache_file_path)?;
+    // SQLite takes the remaining columns from the row with the maximum
+    let query = "
+        SELECT icon_mapping.page_url, favicon_bitmaps.image_data, MAX(favicon_bitmaps.width)
+        FROM icon_mapping
+            JOIN favicon_bitmaps ON icon_mapping.icon_id = favicon_bitmaps.icon_id
+        GROUP BY icon_mapping.page_url";
+    let rows = connection
+        .prepare(query)?
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .context("Error while reading favicons")?;
+
+    let mut favicons = std::collections::HashMap::new();
+    for row in rows {
+        let page_url = row.read::<&str, _>("page_url");
+        let image_data = row.read::<&[u8], _>("image_data");
+
+        let mut hasher = std::hash::DefaultHasher::new();
+        std::hash::Hash::hash(page_url, &mut hasher);
+        let icon_file_path = format!(
+            "{favicons_directory}/{:x}.png",
+            std::hash::Hasher::finish(&hasher)
+        );
+        // Rewriting unchanged icons would invalidate their rasterized images
+        if std::fs::read(&icon_file_path).ok().as_deref() != Some(image_data) {
+            std::fs::write(&icon_file_path, image_data)
+                .context("Error while writing favicon file")?;
+        }
+        favicons.insert(String::from(page_url), icon_file_path);
+    }
+
+    Ok(favicons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bookmark_matches_by_url() {
+        let bookmark: UrlBookmark = serde_json::from_str(
+            r#"{"name": "The Rust Book", "url": "https://doc.rust-lang.org/book/"}"#,
+        )
+        .unwrap();
+        let mut matcher =
+            crate::plugin::matcher::EntryMatcher::new(vec![crate::model::Entry::from(&bookmark)]);
+
+        let matches = matcher.search("lang.org", None, &std::collections::HashMap::new());
+
+        assert_eq!(matches.len(), 1);
+        assert!(matches!(
+            matches[0].highlight,
+            crate::model::Highlight::Meta(_)
+        ));
+    }
+}
@@ -36,6 +36,7 @@ impl Plugin for ClockPlugin {
                 action: String::from(""),
                 meta: String::from("Clock Time"),
                 command: None,
+                ..Default::default()
             },
             crate::model::Entry {
                 id: String::from("date"),
@@ -43,6 +44,7 @@ impl Plugin for ClockPlugin {
                 action: String::from(""),
                 meta: String::from("Clock Date"),
                 command: None,
+                ..Default::default()
             },
         ];
 
@@ -0,0 +1,71 @@
+use crate::plugin::utils::Plugin;
+use anyhow::Context;
+use std::io::BufRead;
+
+pub struct DmenuPlugin {
+    entries: Vec<crate::model::Entry>,
+}
+
+impl Plugin for DmenuPlugin {
+    fn new() -> Self {
+        Self { entries: vec![] }
+    }
+
+    fn id() -> &'static str {
+        "dmenu"
+    }
+
+    fn priority() -> u32 {
+        0
+    }
+
+    fn title() -> &'static str {
+        "󰍜 Items"
+    }
+
+    fn entries(&self) -> Vec<crate::model::Entry> {
+        self.entries.clone()
+    }
+
+    fn set_entries(&mut self, entries: Vec<crate::model::Entry>) {
+        self.entries = entries;
+    }
+
+    // Items keep the order in which they were passed in, like they do in dmenu
+    fn update_entries(&mut self) -> anyhow::Result<()> {
+        self.entries = std::io::stdin()
+            .lock()
+            .lines()
+            .map_while(Result::ok)
+            .filter(|line| !line.is_empty())
+            .enumerate()
+            .map(|(index, line)| crate::model::Entry {
+                id: index.to_string(),
+                title: line,
+                action: String::from("select"),
+                meta: String::new(),
+                command: None,
+                ..Default::default()
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    fn activate(
+        &mut self,
+        entry: crate::model::Entry,
+        plugin_channel_out: &mut async_channel::Sender<crate::Message>,
+    ) -> anyhow::Result<()> {
+        println!("{}", entry.title);
+
+        plugin_channel_out
+            .try_send(crate::Message::Exit)
+            .context(format!(
+                "Failed to send message to exit application while activating entry with id '{}'.",
+                entry.id
+            ))?;
+
+        Ok(())
+    }
+}
@@ -18,6 +18,10 @@ impl Plugin for BookmarksPlugin {
         "󰃃 Bookmarks"
     }
 
+    fn default_keywords() -> &'static [&'static str] {
+        &["b"]
+    }
+
     fn entries(&self) -> Vec<crate::model::Entry> {
         self.entries.clone()
     }
@@ -52,12 +56,13 @@ impl Plugin for BookmarksPlugin {
 
         connection.execute(query)?;
         let url_rows = connection
-            .prepare(query)
-            .unwrap()
+            .prepare(query)?
             .into_iter()
-            .map(|row| row.unwrap());
+            .collect::<Result<Vec<_>, _>>()
+            .context("Error while reading bookmarks")?;
 
         self.entries = url_rows
+            .into_iter()
             .map(|row| {
                 let title = row.read::<&str, _>("title");
                 let url = row.read::<&str, _>("url");
@@ -65,9 +70,11 @@ impl Plugin for BookmarksPlugin {
                 crate::model::Entry {
                     id: url.to_string(),
                     title: title.to_string(),
+                    subtitle: Some(url.to_string()),
                     action: String::from("open"),
-                    meta: String::from("Bookmarks"),
+                    meta: format!("Bookmarks {url}"),
                     command: None,
+                    ..Default::default()
                 }
             })
             .collect();
@@ -18,6 +18,10 @@ impl Plugin for HistoryPlugin {
         "󰋚 History"
     }
 
+    fn default_keywords() -> &'static [&'static str] {
+        &["h"]
+    }
+
     fn entries(&self) -> Vec<crate::model::Entry> {
         self.entries.clone()
     }
@@ -50,12 +54,13 @@ impl Plugin for HistoryPlugin {
         connection.execute(query)?;
 
         let url_rows = connection
-            .prepare(query)
-            .unwrap()
+            .prepare(query)?
             .into_iter()
-            .map(|row| row.unwrap());
+            .collect::<Result<Vec<_>, _>>()
+            .context("Error while reading history")?;
 
         self.entries = url_rows
+            .into_iter()
             .map(|row| {
                 let title = row.read::<Option<&str>, _>("title");
                 let url = row.read::<&str, _>("url");
@@ -63,9 +68,11 @@ impl Plugin for HistoryPlugin {
                 crate::model::Entry {
                     id: url.to_string(),
                     title: title.unwrap_or(url).to_string(),
+                    subtitle: Some(url.to_string()),
                     action: String::from("open"),
-                    meta: String::from("History"),
+                    meta: format!("History {url}"),
                     command: None,
+                    ..Default::default()
                 }
             })
             .collect();
@@ -1,9 +1,9 @@
-use crate::plugin::utils::Plugin;
+use crate::plugin::utils::{ConfigurablePlugin, Plugin};
 use anyhow::Context;
 
 pub struct GitRepositoriesPlugin {
     entries: Vec<crate::model::Entry>,
-    settings: settings::Settings,
+    settings: settings::GitRepositoriesPluginSettings,
 }
 
 impl Plugin for GitRepositoriesPlugin {
@@ -19,6 +19,10 @@ impl Plugin for GitRepositoriesPlugin {
         "󰘬 Git Repositories"
     }
 
+    fn default_keywords() -> &'static [&'static str] {
+        &["g"]
+    }
+
     fn entries(&self) -> Vec<crate::model::Entry> {
         self.entries.clone()
     }
@@ -28,10 +32,7 @@ impl Plugin for GitRepositoriesPlugin {
     }
 
     fn new() -> Self {
-        Self {
-            entries: vec![],
-            settings: settings::Settings::new(),
-        }
+        Self::from_settings(settings::GitRepositoriesPluginSettings::default())
     }
 
     // This lint seems to be a false positive
@@ -39,22 +40,46 @@ impl Plugin for GitRepositoriesPlugin {
     fn update_entries(&mut self) -> anyhow::Result<()> {
         self.entries.clear();
 
-        let git_repository_paths: Vec<String> =
-            crate::plugin::utils::read_index_file("git-repositories-index.json")?;
+        let git_repository_paths: Vec<String> = crate::plugin::utils::read_index_file(
+            "git-repositories-index.json",
+        )
+        .map_err(|error| {
+            let message = format!("{error} — run index-git-repositories");
+            error.context(message)
+        })?;
 
         let home = std::env::var("HOME").unwrap_or(String::from(""));
 
+        // Every configured command can also be run on its own from the action menu
+        let mut actions: Vec<crate::model::Action> = self
+            .settings
+            .commands
+            .iter()
+            .enumerate()
+            .map(|(index, command)| crate::model::Action {
+                id: format!("command-{index}"),
+                title: command.join(" "),
+            })
+            .collect();
+        actions.push(crate::model::Action {
+            id: String::from("copy-path"),
+            title: String::from("copy path"),
+        });
+
         let entries = git_repository_paths
             .into_iter()
             .filter_map(|git_repository_path| {
                 let git_repository_display_name = git_repository_path.replacen(&home, "~", 1);
 
                 Some(crate::model::Entry {
-                    id: git_repository_path,
+                    id: git_repository_path.clone(),
                     title: git_repository_display_name,
+                    subtitle: Some(git_repository_path),
                     action: String::from("focus"),
                     meta: String::from("Git Repositories"),
                     command: None,
+                    actions: actions.clone(),
+                    ..Default::default()
                 })
             })
             .collect::<Vec<_>>();
@@ -70,24 +95,44 @@ impl Plugin for GitRepositoriesPlugin {
         entry: crate::model::Entry,
         plugin_channel_out: &mut async_channel::Sender<crate::Message>,
     ) -> anyhow::Result<()> {
-        for command in self.settings.plugin.git_repositories.commands.clone() {
-            let parsed_command: Vec<String> = command
-                .into_iter()
-                .map(|command_part| match command_part.as_ref() {
-                    "$GIT_DIRECTORY" => entry.id.clone(),
-                    "$GIT_DIRECTORY_NAME" => std::path::Path::new(&entry.id)
-                        .file_name()
-                        // We match on a git directory
-                        .unwrap()
-                        .to_str()
-                        .unwrap()
-                        .into(),
-                    _ => command_part,
-                })
-                .collect();
-            std::process::Command::new(&parsed_command[0])
-                .args(&parsed_command[1..])
-                .spawn()?;
+        for command in self.settings.commands.clone() {
+            Self::run_command(command, &entry)?;
+        }
+
+        plugin_channel_out
+            .try_send(crate::Message::Exit)
+            .context(format!(
+                "Failed to send message to exit application while activating entry with id '{}'.",
+                entry.id
+            ))?;
+
+        Ok(())
+    }
+
+    fn activate_action(
+        &mut self,
+        entry: crate::model::Entry,
+        action_id: &str,
+        plugin_channel_out: &mut async_channel::Sender<crate::Message>,
+    ) -> anyhow::Result<()> {
+        if action_id == "copy-path" {
+            std::process::Command::new("wl-copy")
+                .arg(&entry.id)
+                .spawn()
+                .context(format!(
+                    "Failed to copy path while activating entry with id '{}'.",
+                    entry.id
+                ))?;
+        } else {
+            let command = action_id
+                .strip_prefix("command-")
+                .and_then(|index| index.parse::<usize>().ok())
+                .and_then(|index| self.settings.commands.get(index))
+                .context(format!(
+                    "Unknown action '{}' of entry with id '{}'.",
+                    action_id, entry.id
+                ))?;
+            Self::run_command(command.clone(), &entry)?;
         }
 
         plugin_channel_out
@@ -114,9 +159,41 @@ impl Plugin for GitRepositoriesPlugin {
     }
 }
 
+impl ConfigurablePlugin for GitRepositoriesPlugin {
+    type Settings = settings::GitRepositoriesPluginSettings;
+
+    fn from_settings(settings: Self::Settings) -> Self {
+        Self {
+            entries: vec![],
+            settings,
+        }
+    }
+}
+
 impl GitRepositoriesPlugin {
+    fn run_command(command: Vec<String>, entry: &crate::model::Entry) -> anyhow::Result<()> {
+        let parsed_command: Vec<String> = command
+            .into_iter()
+            .map(|command_part| match command_part.as_ref() {
+                "$GIT_DIRECTORY" => entry.id.clone(),
+                "$GIT_DIRECTORY_NAME" => std::path::Path::new(&entry.id)
+                    .file_name()
+                    // We match on a git directory
+                    .unwrap()
+                    .to_str()
+                    .unwrap()
+                    .into(),
+                _ => command_part,
+            })
+            .collect();
+        std::process::Command::new(&parsed_command[0])
+            .args(&parsed_command[1..])
+            .spawn()?;
+        Ok(())
+    }
+
     fn use_zoxide(&self) -> bool {
-        self.settings.plugin.git_repositories.zoxide
+        self.settings.zoxide
     }
     /// Sorts the returned paths, by their respective zoxide query score
     fn sort_with_zoxide(&mut self, index: Zoxide) {
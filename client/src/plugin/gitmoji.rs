@@ -327,6 +327,10 @@ impl Plugin for GitmojiPlugin {
         "󰞅 Gitmoji"
     }
 
+    fn enable_by_default() -> bool {
+        false
+    }
+
     fn update_entries(&mut self) -> anyhow::Result<()> {
         self.entries.clear();
 
@@ -338,6 +342,7 @@ impl Plugin for GitmojiPlugin {
                 action: String::from("copy"),
                 meta: String::from("Gitmoji"),
                 command: None,
+                ..Default::default()
             })
             .collect();
 
@@ -0,0 +1,287 @@
+use crate::model::{Entry, Highlight};
+use nucleo_matcher::{
+    Matcher, Utf32Str, Utf32String,
+    pattern::{Atom, AtomKind, CaseMatching, Normalization},
+};
+use std::collections::HashMap;
+
+/// Number of entries from which scoring is spread over several threads.
+const PARALLEL_THRESHOLD: usize = 10_000;
+
+/// Added to the score of title matches, they always rank above meta matches.
+const TITLE_BONUS: u32 = 1000;
+
+/// The entries of a plugin prepared for repeated searches.
+///
+/// Titles and metas are encoded for matching once, not on every search. A query extending the
+/// previous query only scores the entries which matched the previous query.
+#[derive(Debug, Default)]
+pub struct EntryMatcher {
+    entries: Vec<Entry>,
+    titles: Vec<Utf32String>,
+    metas: Vec<Utf32String>,
+    /// The previous query and the indices of the entries matching it
+    previous: Option<(String, Vec<usize>)>,
+}
+
+impl EntryMatcher {
+    pub fn new(entries: Vec<Entry>) -> Self {
+        Self {
+            titles: entries
+                .iter()
+                .map(|entry| Utf32String::from(entry.title.as_str()))
+                .collect(),
+            metas: entries
+                .iter()
+                .map(|entry| Utf32String::from(entry.meta.as_str()))
+                .collect(),
+            entries,
+            previous: None,
+        }
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Matches the terms of the query, separated by spaces, against the title and the meta of
+    /// the entries. Entries match if every term matches. The frecency score of an entry is
+    /// added to its match score.
+    ///
+    /// Like in fzf, terms match fuzzy against the title, falling back to substring matching
+    /// against the meta. `'term` matches exactly, `^term` at the start, `term$` at the end,
+    /// and `!term` excludes entries containing the term.
+    ///
+    /// Returns the best matching entries first, at most `limit` entries if set. Entries with
+    /// equal scores keep their order.
+    pub fn search(
+        &mut self,
+        query: &str,
+        limit: Option<usize>,
+        frecency_scores: &HashMap<String, u32>,
+    ) -> Vec<Entry> {
+        let pattern = Pattern::new(query);
+        let candidates = match self.previous.take() {
+            Some((previous_query, indices)) if narrows(&previous_query, query) => indices,
+            _ => (0..self.entries.len()).collect(),
+        };
+
+        let mut matches = self.score(&pattern, &candidates, frecency_scores);
+        self.previous = Some((
+            String::from(query),
+            matches.iter().map(|(_, index)| *index).collect(),
+        ));
+
+        let by_score = |(score, index): &(u32, usize),
+                        (other_score, other_index): &(u32, usize)| {
+            other_score.cmp(score).then(index.cmp(other_index))
+        };
+        // Only the entries which are returned need to be sorted
+        if let Some(limit) = limit
+            && limit < matches.len()
+        {
+            matches.select_nth_unstable_by(limit, by_score);
+            matches.truncate(limit);
+        }
+        matches.sort_unstable_by(by_score);
+
+        let mut matchers = Matchers::new();
+        matches
+            .into_iter()
+            .map(|(score, index)| Entry {
+                score,
+                highlight: pattern.highlight(
+                    self.titles[index].slice(..),
+                    self.metas[index].slice(..),
+                    &mut matchers,
+                ),
+                ..self.entries[index].clone()
+            })
+            .collect()
+    }
+
+    /// Scores of the matching candidates together with their index, in the order of the
+    /// candidates.
+    fn score(
+        &self,
+        pattern: &Pattern,
+        candidates: &[usize],
+        frecency_scores: &HashMap<String, u32>,
+    ) -> Vec<(u32, usize)> {
+        let threads = std::thread::available_parallelism().map_or(1, std::num::NonZero::get);
+        if threads == 1 || candidates.len() < PARALLEL_THRESHOLD {
+            return self.score_chunk(pattern, candidates, frecency_scores);
+        }
+
+        let chunk_size = candidates.len().div_ceil(threads);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = candidates
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(|| self.score_chunk(pattern, chunk, frecency_scores)))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_default())
+                .collect()
+        })
+    }
+
+    fn score_chunk(
+        &self,
+        pattern: &Pattern,
+        candidates: &[usize],
+        frecency_scores: &HashMap<String, u32>,
+    ) -> Vec<(u32, usize)> {
+        let mut matchers = Matchers::new();
+        candidates
+            .iter()
+            .filter_map(|&index| {
+                let score = pattern.score(
+                    self.titles[index].slice(..),
+                    self.metas[index].slice(..),
+                    &mut matchers,
+                )?;
+                let frecency_score = frecency_scores.get(&self.entries[index].id);
+                Some((score + frecency_score.unwrap_or(&0), index))
+            })
+            .collect()
+    }
+}
+
+/// Whether entries matching the query also match the previous query, so only those need to be
+/// scored.
+///
+/// Typing makes the query stricter, except for extending a negated term, a term matching at
+/// the end or an escape sequence.
+fn narrows(previous_query: &str, query: &str) -> bool {
+    if !query.starts_with(previous_query) {
+        return false;
+    }
+    let last_term = previous_query.rsplit(' ').next().unwrap_or_default();
+    !(last_term.starts_with('!') || last_term.ends_with('$') || last_term.ends_with('\\'))
+}
+
+/// A term of the query, matched against the title and the meta of the entries.
+struct Term {
+    title: Atom,
+    /// Plain terms match fuzzy against titles but as substring against metas, e.g. urls
+    meta: Atom,
+}
+
+/// The terms of the query.
+struct Pattern {
+    terms: Vec<Term>,
+}
+
+/// Matchers hold buffers, every thread needs its own.
+struct Matchers {
+    title: Matcher,
+    meta: Matcher,
+}
+
+impl Matchers {
+    fn new() -> Self {
+        let mut title_config = nucleo_matcher::Config::DEFAULT;
+        title_config.prefer_prefix = true; // Higher score to matches earlier in the string
+        Self {
+            title: Matcher::new(title_config),
+            meta: Matcher::new(nucleo_matcher::Config::DEFAULT),
+        }
+    }
+}
+
+impl Pattern {
+    fn new(query: &str) -> Self {
+        let pattern = nucleo_matcher::pattern::Pattern::parse(
+            query,
+            CaseMatching::Ignore,
+            Normalization::Smart,
+        );
+        let terms = pattern
+            .atoms
+            .into_iter()
+            .map(|title| {
+                let mut meta = title.clone();
+                if meta.kind == AtomKind::Fuzzy {
+                    meta.kind = AtomKind::Substring;
+                }
+                Term { title, meta }
+            })
+            .collect();
+        Self { terms }
+    }
+
+    /// The sum of the scores of the terms, entries matching every term in the title rank
+    /// above entries matching some terms only in the meta.
+    fn score(&self, title: Utf32Str, meta: Utf32Str, matchers: &mut Matchers) -> Option<u32> {
+        let mut score = 0;
+        let mut title_match = true;
+        for term in &self.terms {
+            // Negated terms score nothing, they only exclude entries matching them anywhere
+            if term.title.negative {
+                term.title.score(title, &mut matchers.title)?;
+                term.meta.score(meta, &mut matchers.meta)?;
+                continue;
+            }
+
+            match term.title.score(title, &mut matchers.title) {
+                Some(term_score) => score += u32::from(term_score),
+                None => {
+                    score += u32::from(term.meta.score(meta, &mut matchers.meta)?);
+                    title_match = false;
+                }
+            }
+        }
+
+        Some(if title_match {
+            score + TITLE_BONUS
+        } else {
+            score
+        })
+    }
+
+    /// The characters matching the terms, in the title if any term matches the title, an
+    /// empty query matches without any.
+    fn highlight(&self, title: Utf32Str, meta: Utf32Str, matchers: &mut Matchers) -> Highlight {
+        let mut title_indices = Vec::new();
+        let mut meta_indices = Vec::new();
+        let mut term_indices = Vec::new();
+        for term in self.terms.iter().filter(|term| !term.title.negative) {
+            term_indices.clear();
+            if term
+                .title
+                .indices(title, &mut matchers.title, &mut term_indices)
+                .is_some()
+            {
+                title_indices.append(&mut term_indices);
+                continue;
+            }
+
+            term_indices.clear();
+            if term
+                .meta
+                .indices(meta, &mut matchers.meta, &mut term_indices)
+                .is_some()
+            {
+                meta_indices.append(&mut term_indices);
+            }
+        }
+
+        let title_match = !title_indices.is_empty();
+        let mut indices = if title_match {
+            title_indices
+        } else {
+            meta_indices
+        };
+        if indices.is_empty() {
+            return Highlight::None;
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        if title_match {
+            Highlight::Title(indices)
+        } else {
+            Highlight::Meta(indices)
+        }
+    }
+}
@@ -1,11 +1,15 @@
 pub mod applications;
 pub mod brave;
 pub mod clock;
+pub mod dmenu;
 pub mod firefox;
 pub mod git_repositories;
 pub mod gitmoji;
+pub mod matcher;
 pub mod niri_windows;
+pub mod registry;
 pub mod resource_monitor;
+pub mod script;
 pub mod sway_windows;
 pub mod system;
 pub mod utils;
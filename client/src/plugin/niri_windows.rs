@@ -7,7 +7,7 @@ pub struct NiriWindowsPlugin {
 
 impl Plugin for NiriWindowsPlugin {
     fn id() -> &'static str {
-        "niri-windows"
+        "niri_windows"
     }
     fn priority() -> u32 {
         30
@@ -15,6 +15,13 @@ impl Plugin for NiriWindowsPlugin {
     fn title() -> &'static str {
         "󰖯 Windows"
     }
+    fn update_on_show() -> bool {
+        true
+    }
+
+    fn default_keywords() -> &'static [&'static str] {
+        &["w"]
+    }
 
     fn entries(&self) -> Vec<crate::model::Entry> {
         self.entries.clone()
@@ -29,6 +36,12 @@ impl Plugin for NiriWindowsPlugin {
     }
 
     fn update_entries(&mut self) -> anyhow::Result<()> {
+        // Stay empty instead of reporting an error when running under another compositor
+        if std::env::var_os(niri_ipc::socket::SOCKET_PATH_ENV).is_none() {
+            self.entries.clear();
+            return Ok(());
+        }
+
         let socket =
             niri_ipc::socket::Socket::connect().context("Failed to connect to niri IPC socket.")?;
 
@@ -42,6 +55,15 @@ impl Plugin for NiriWindowsPlugin {
             Err(msg) => anyhow::bail!("Niri IPC error: {}", msg),
         };
 
+        // Named workspaces are shown by name, others by their index on their output
+        let workspace_names: std::collections::HashMap<u64, String> = Self::workspaces()?
+            .into_iter()
+            .map(|workspace| {
+                let name = workspace.name.unwrap_or_else(|| workspace.idx.to_string());
+                (workspace.id, name)
+            })
+            .collect();
+
         let entries: Vec<crate::model::Entry> = windows
             .into_iter()
             .map(|window| {
@@ -53,9 +75,25 @@ impl Plugin for NiriWindowsPlugin {
                 crate::model::Entry {
                     id: window.id.to_string(),
                     title,
+                    subtitle: window
+                        .workspace_id
+                        .and_then(|workspace_id| workspace_names.get(&workspace_id))
+                        .map(|workspace| format!("workspace {workspace}")),
                     action: String::from("focus"),
                     meta: String::from("Niri Windows"),
                     command: None,
+                    actions: vec![
+                        crate::model::Action {
+                            id: String::from("close"),
+                            title: String::from("close"),
+                        },
+                        crate::model::Action {
+                            id: String::from("move-here"),
+                            title: String::from("move to current workspace"),
+                        },
+                    ],
+                    icon: window.app_id,
+                    ..Default::default()
                 }
             })
             .collect();
@@ -76,20 +114,62 @@ impl Plugin for NiriWindowsPlugin {
             .parse()
             .context("Failed to parse window id as u64.")?;
 
-        let socket = niri_ipc::socket::Socket::connect()
-            .context("Failed to connect to niri IPC socket for focus action.")?;
+        Self::run_action(niri_ipc::Action::FocusWindow { id: window_id }).context(format!(
+            "Failed to focus window while activating entry with id '{}'.",
+            entry.id
+        ))?;
 
-        let (reply, _) = socket
-            .send(niri_ipc::Request::Action(niri_ipc::Action::FocusWindow {
-                id: window_id,
-            }))
+        plugin_channel_out
+            .try_send(crate::Message::Exit)
             .context(format!(
-                "Failed to focus window while activating entry with id '{}'.",
+                "Failed to send message to exit application while activating entry with id '{}'.",
                 entry.id
             ))?;
 
-        if let Err(msg) = reply {
-            anyhow::bail!("Niri IPC error focusing window: {}", msg);
+        Ok(())
+    }
+
+    fn activate_action(
+        &mut self,
+        entry: crate::model::Entry,
+        action_id: &str,
+        plugin_channel_out: &mut async_channel::Sender<crate::Message>,
+    ) -> anyhow::Result<()> {
+        let window_id: u64 = entry
+            .id
+            .parse()
+            .context("Failed to parse window id as u64.")?;
+
+        match action_id {
+            "close" => Self::run_action(niri_ipc::Action::CloseWindow {
+                id: Some(window_id),
+            })
+            .context(format!(
+                "Failed to close window while activating entry with id '{}'.",
+                entry.id
+            ))?,
+            "move-here" => {
+                let workspace_id = Self::focused_workspace_id()?;
+                Self::run_action(niri_ipc::Action::MoveWindowToWorkspace {
+                    window_id: Some(window_id),
+                    reference: niri_ipc::WorkspaceReferenceArg::Id(workspace_id),
+                })
+                .context(format!(
+                    "Failed to move window while activating entry with id '{}'.",
+                    entry.id
+                ))?;
+                Self::run_action(niri_ipc::Action::FocusWindow { id: window_id }).context(
+                    format!(
+                        "Failed to focus window while activating entry with id '{}'.",
+                        entry.id
+                    ),
+                )?;
+            }
+            _ => anyhow::bail!(
+                "Unknown action '{}' of entry with id '{}'.",
+                action_id,
+                entry.id
+            ),
         }
 
         plugin_channel_out
@@ -102,3 +182,42 @@ impl Plugin for NiriWindowsPlugin {
         Ok(())
     }
 }
+
+impl NiriWindowsPlugin {
+    fn run_action(action: niri_ipc::Action) -> anyhow::Result<()> {
+        let socket =
+            niri_ipc::socket::Socket::connect().context("Failed to connect to niri IPC socket.")?;
+
+        let (reply, _) = socket
+            .send(niri_ipc::Request::Action(action))
+            .context("Failed to send Action request to niri IPC.")?;
+
+        if let Err(msg) = reply {
+            anyhow::bail!("Niri IPC error: {}", msg);
+        }
+        Ok(())
+    }
+
+    fn workspaces() -> anyhow::Result<Vec<niri_ipc::Workspace>> {
+        let socket =
+            niri_ipc::socket::Socket::connect().context("Failed to connect to niri IPC socket.")?;
+
+        let (reply, _) = socket
+            .send(niri_ipc::Request::Workspaces)
+            .context("Failed to send Workspaces request to niri IPC.")?;
+
+        match reply {
+            Ok(niri_ipc::Response::Workspaces(workspaces)) => Ok(workspaces),
+            Ok(other) => anyhow::bail!("Unexpected niri IPC response: {:?}", other),
+            Err(msg) => anyhow::bail!("Niri IPC error: {}", msg),
+        }
+    }
+
+    fn focused_workspace_id() -> anyhow::Result<u64> {
+        Self::workspaces()?
+            .into_iter()
+            .find(|workspace| workspace.is_focused)
+            .map(|workspace| workspace.id)
+            .context("Failed to find the focused niri workspace.")
+    }
+}
@@ -0,0 +1,318 @@
+use crate::plugin::utils::{ConfigurablePlugin, Plugin};
+use anyhow::Context;
+
+/// A running plugin. The same plugin can run in more than one instance with different settings.
+#[derive(Debug, Clone)]
+pub struct PluginInstance {
+    /// Key of the config section of the instance, the plugin id for default instances
+    pub id: String,
+    pub title: String,
+    pub priority: u32,
+    pub keywords: Vec<String>,
+    /// Number of entries shown at most, all matching entries if unset
+    pub max_results: Option<usize>,
+    /// Number of characters the query needs before entries are shown
+    pub min_query_length: usize,
+    /// Whether entries are only shown while a keyword scopes the search to the plugin
+    pub only_when_scoped: bool,
+}
+
+impl PluginInstance {
+    /// The instance of a plugin with the defaults of the plugin.
+    pub fn of<PluginType: Plugin>() -> Self {
+        Self {
+            id: String::from(PluginType::id()),
+            title: String::from(PluginType::title()),
+            priority: PluginType::priority(),
+            keywords: PluginType::default_keywords()
+                .iter()
+                .map(|keyword| String::from(*keyword))
+                .collect(),
+            max_results: None,
+            min_query_length: 0,
+            only_when_scoped: false,
+        }
+    }
+
+    /// The instance showing every matching entry for every query.
+    pub fn without_result_limits(self) -> Self {
+        Self {
+            max_results: None,
+            min_query_length: 0,
+            only_when_scoped: false,
+            ..self
+        }
+    }
+}
+
+type SpawnFn = fn(
+    PluginInstance,
+    serde_yaml::Value,
+) -> anyhow::Result<async_channel::Receiver<crate::Message>>;
+
+/// Returns the keys of a config section which are not plugin settings.
+type CheckFn = fn(serde_yaml::Value) -> Result<Vec<String>, serde_yaml::Error>;
+
+/// A plugin which can be configured in the `plugin` section of the config.
+struct Registration {
+    id: &'static str,
+    enable: bool,
+    default_instance: fn() -> PluginInstance,
+    spawn: SpawnFn,
+    check: CheckFn,
+}
+
+/// The settings of plugins without settings of their own.
+#[derive(serde::Deserialize)]
+struct NoSettings {}
+
+fn register<PluginType: Plugin + std::marker::Send + 'static>() -> Registration {
+    Registration {
+        id: PluginType::id(),
+        enable: PluginType::enable_by_default(),
+        default_instance: PluginInstance::of::<PluginType>,
+        spawn: |instance, _settings| Ok(crate::plugin::utils::spawn(instance, PluginType::new)),
+        check: |section| {
+            settings::check::with_unknown_keys::<NoSettings>(section).map(|(_, keys)| keys)
+        },
+    }
+}
+
+fn register_configurable<PluginType: ConfigurablePlugin + std::marker::Send + 'static>()
+-> Registration {
+    Registration {
+        id: PluginType::id(),
+        enable: PluginType::enable_by_default(),
+        default_instance: PluginInstance::of::<PluginType>,
+        spawn: |instance, settings| {
+            let settings: PluginType::Settings =
+                serde_yaml::from_value(settings).context(format!(
+                    "Config section of plugin instance '{}' does not match the plugin settings.",
+                    instance.id
+                ))?;
+            Ok(crate::plugin::utils::spawn(instance, move || {
+                PluginType::from_settings(settings)
+            }))
+        },
+        check: |section| {
+            settings::check::with_unknown_keys::<PluginType::Settings>(section)
+                .map(|(_, keys)| keys)
+        },
+    }
+}
+
+fn registrations() -> Vec<Registration> {
+    vec![
+        register::<crate::plugin::applications::ApplicationsPlugin>(),
+        register::<crate::plugin::brave::bookmarks::BookmarksPlugin>(),
+        register::<crate::plugin::brave::progressive_web_apps::ProgressiveWebAppsPlugin>(),
+        register::<crate::plugin::brave::history::HistoryPlugin>(),
+        register::<crate::plugin::clock::ClockPlugin>(),
+        register::<crate::plugin::firefox::bookmarks::BookmarksPlugin>(),
+        register::<crate::plugin::firefox::history::HistoryPlugin>(),
+        register_configurable::<crate::plugin::git_repositories::GitRepositoriesPlugin>(),
+        register::<crate::plugin::gitmoji::GitmojiPlugin>(),
+        register::<crate::plugin::resource_monitor::battery::BatteryPlugin>(),
+        register::<crate::plugin::resource_monitor::cpu::CpuPlugin>(),
+        register::<crate::plugin::resource_monitor::disks::DisksPlugin>(),
+        register::<crate::plugin::resource_monitor::memory::MemoryPlugin>(),
+        register::<crate::plugin::system::SystemPlugin>(),
+        register::<crate::plugin::wifi::WifiPlugin>(),
+        register::<crate::plugin::niri_windows::NiriWindowsPlugin>(),
+        register::<crate::plugin::sway_windows::SwayWindowsPlugin>(),
+        register_configurable::<crate::plugin::script::ScriptPlugin>(),
+    ]
+}
+
+/// A plugin instance read from the config, ready to be spawned.
+pub struct ConfiguredInstance {
+    pub instance: PluginInstance,
+    settings: serde_yaml::Value,
+    spawn: SpawnFn,
+}
+
+impl ConfiguredInstance {
+    /// The config section of the instance.
+    pub fn settings(&self) -> &serde_yaml::Value {
+        &self.settings
+    }
+
+    pub fn spawn(self) -> anyhow::Result<async_channel::Receiver<crate::Message>> {
+        (self.spawn)(self.instance, self.settings)
+    }
+}
+
+/// The enabled plugin instances.
+///
+/// Every plugin runs once under its own id. Config sections naming a plugin with `type`
+/// add further instances of that plugin.
+pub fn instances(settings: &settings::Settings) -> Vec<ConfiguredInstance> {
+    let registrations = registrations();
+
+    let mut sections = settings.plugin.clone();
+    for registration in registrations.iter() {
+        sections
+            .entry(String::from(registration.id))
+            .or_insert(serde_yaml::Value::Null);
+    }
+
+    let mut instances = vec![];
+    for (instance_id, section) in sections {
+        // A section without any keys, e.g. `clock:`, configures nothing
+        let section = match section {
+            serde_yaml::Value::Null => serde_yaml::Value::Mapping(serde_yaml::Mapping::new()),
+            section => section,
+        };
+        let instance_settings: settings::PluginInstanceSettings =
+            match serde_yaml::from_value(section.clone()) {
+                Ok(instance_settings) => instance_settings,
+                Err(error) => {
+                    log::error!(
+                        "Skipping plugin instance '{}' with invalid config: {}",
+                        instance_id,
+                        error
+                    );
+                    continue;
+                }
+            };
+
+        let plugin_id = instance_settings
+            .plugin
+            .clone()
+            .unwrap_or_else(|| instance_id.clone());
+        let Some(registration) = registrations
+            .iter()
+            .find(|registration| registration.id == plugin_id)
+        else {
+            log::error!(
+                "Skipping plugin instance '{}' of unknown plugin '{}'",
+                instance_id,
+                plugin_id
+            );
+            continue;
+        };
+
+        // Additional instances are enabled by adding them to the config
+        let enable = instance_settings
+            .enable
+            .unwrap_or(registration.enable || instance_id != plugin_id);
+        if !enable {
+            continue;
+        }
+
+        let defaults = (registration.default_instance)();
+        instances.push(ConfiguredInstance {
+            instance: PluginInstance {
+                id: instance_id,
+                title: instance_settings.title.unwrap_or(defaults.title),
+                priority: instance_settings.priority.unwrap_or(defaults.priority),
+                keywords: instance_settings.keywords.unwrap_or(defaults.keywords),
+                max_results: instance_settings.max_results.or(defaults.max_results),
+                min_query_length: instance_settings
+                    .min_query_length
+                    .unwrap_or(defaults.min_query_length),
+                only_when_scoped: instance_settings
+                    .only_when_scoped
+                    .unwrap_or(defaults.only_when_scoped),
+            },
+            settings: section,
+            spawn: registration.spawn,
+        });
+    }
+
+    instances
+}
+
+/// Checks the config sections of the plugin instances for unknown plugins, values not matching
+/// the settings of the plugin and unknown keys.
+pub fn check(settings: &settings::Settings) -> Vec<settings::check::Diagnostic> {
+    use settings::check::Diagnostic;
+
+    let registrations = registrations();
+    let mut diagnostics = vec![];
+    for (instance_id, section) in &settings.plugin {
+        let key = format!("plugin.{instance_id}");
+        let section = match section {
+            serde_yaml::Value::Null => serde_yaml::Value::Mapping(serde_yaml::Mapping::new()),
+            section => section.clone(),
+        };
+        let instance_result =
+            settings::check::with_unknown_keys::<settings::PluginInstanceSettings>(section.clone());
+        let (instance_settings, instance_keys) = match instance_result {
+            Ok(instance_settings) => instance_settings,
+            Err(error) => {
+                diagnostics.push(Diagnostic::error(key, error.to_string()));
+                continue;
+            }
+        };
+
+        let plugin_id = instance_settings
+            .plugin
+            .clone()
+            .unwrap_or_else(|| instance_id.clone());
+        let Some(registration) = registrations
+            .iter()
+            .find(|registration| registration.id == plugin_id)
+        else {
+            let (key, message) = match instance_settings.plugin {
+                Some(_) => (
+                    format!("{key}.type"),
+                    format!("Unknown plugin '{plugin_id}'"),
+                ),
+                None => (
+                    key,
+                    String::from(
+                        "Unknown plugin, set `type` for an additional instance of a plugin",
+                    ),
+                ),
+            };
+            let suggestion = registrations
+                .iter()
+                .map(|registration| registration.id)
+                .filter(|id| edit_distance(id, &plugin_id) <= 2)
+                .min_by_key(|id| edit_distance(id, &plugin_id));
+            let message = match suggestion {
+                Some(id) => format!("{message}, did you mean '{id}'?"),
+                None => format!("{message}."),
+            };
+            diagnostics.push(Diagnostic::error(key, message));
+            continue;
+        };
+
+        let plugin_keys = match (registration.check)(section) {
+            Ok(plugin_keys) => plugin_keys,
+            Err(error) => {
+                diagnostics.push(Diagnostic::error(key, error.to_string()));
+                continue;
+            }
+        };
+        // Keys are unknown if neither the instance nor the plugin uses them
+        for unknown_key in instance_keys
+            .iter()
+            .filter(|instance_key| plugin_keys.contains(instance_key))
+        {
+            diagnostics.push(Diagnostic::warning(
+                format!("{key}.{unknown_key}"),
+                "Unknown key, it is ignored.",
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+/// Number of inserted, removed or replaced characters to turn one text into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut previous_diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous_diagonal + usize::from(a_char != *b_char);
+            previous_diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
@@ -66,6 +66,7 @@ impl Plugin for BatteryPlugin {
                 action: String::from(""),
                 meta: String::from("Resource Monitor Battery"),
                 command: None,
+                ..Default::default()
             });
         }
 
@@ -58,6 +58,7 @@ impl Plugin for DisksPlugin {
                 action: String::from(""),
                 meta: String::from("Resource Monitor Disks"),
                 command: None,
+                ..Default::default()
             });
         }
 
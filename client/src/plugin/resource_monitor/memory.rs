@@ -49,6 +49,7 @@ impl Plugin for MemoryPlugin {
             action: String::from(""),
             meta: String::from("Resource Monitor Memory RAM"),
             command: None,
+            ..Default::default()
         });
 
         Ok(())
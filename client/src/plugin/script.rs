@@ -0,0 +1,427 @@
+use crate::plugin::utils::{ConfigurablePlugin, Plugin};
+use anyhow::Context;
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex};
+
+/// Requests written to the stdin of the script, one json object per line.
+#[derive(Debug, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ScriptRequest<'a> {
+    Search {
+        query: &'a str,
+        /// Echoed by scripts answering searches themselves, see [`ScriptMessage`]
+        generation: u64,
+    },
+    Timeout,
+    Activate {
+        entry: ScriptEntry,
+        action: Option<&'a str>,
+    },
+}
+
+/// Messages read from the stdout of the script, one json object per line.
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ScriptMessage {
+    UpdateEntries {
+        entries: Vec<ScriptEntry>,
+        /// The generation of the search the entries answer, if they answer one
+        #[serde(default)]
+        generation: Option<u64>,
+    },
+    Exit,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct ScriptEntry {
+    id: String,
+    title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    subtitle: Option<String>,
+    #[serde(default)]
+    action: String,
+    #[serde(default)]
+    meta: String,
+    #[serde(default)]
+    actions: Vec<ScriptAction>,
+    /// Name of an icon of the icon theme or an absolute path to an image file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    icon: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct ScriptAction {
+    id: String,
+    title: String,
+}
+
+impl From<ScriptEntry> for crate::model::Entry {
+    fn from(entry: ScriptEntry) -> Self {
+        crate::model::Entry {
+            id: entry.id,
+            title: entry.title,
+            subtitle: entry.subtitle,
+            action: entry.action,
+            meta: entry.meta,
+            command: None,
+            actions: entry
+                .actions
+                .into_iter()
+                .map(|action| crate::model::Action {
+                    id: action.id,
+                    title: action.title,
+                })
+                .collect(),
+            icon: entry.icon,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<crate::model::Entry> for ScriptEntry {
+    fn from(entry: crate::model::Entry) -> Self {
+        ScriptEntry {
+            id: entry.id,
+            title: entry.title,
+            subtitle: entry.subtitle,
+            action: entry.action,
+            meta: entry.meta,
+            actions: entry
+                .actions
+                .into_iter()
+                .map(|action| ScriptAction {
+                    id: action.id,
+                    title: action.title,
+                })
+                .collect(),
+            icon: entry.icon,
+        }
+    }
+}
+
+/// Runs an executable declared in the config and talks to it over stdin and stdout.
+///
+/// Every script runs in its own plugin instance.
+pub struct ScriptPlugin {
+    settings: settings::ScriptPluginSettings,
+    /// Id of the plugin instance, known once the script is started
+    instance_id: String,
+    stdin: Option<std::process::ChildStdin>,
+    /// Shared with the thread reading the stdout of the script
+    state: Arc<Mutex<ScriptState>>,
+}
+
+#[derive(Default)]
+struct ScriptState {
+    /// Entries last sent by the script, `None` until the script sent its first entries
+    matcher: Option<crate::plugin::matcher::EntryMatcher>,
+    /// The last search, `None` until the first search
+    search: Option<crate::model::Search>,
+    /// The running script, taken by whoever stops it
+    child: Option<std::process::Child>,
+}
+
+impl ScriptState {
+    /// The entries to show once both the entries and the query are known.
+    fn update_entries_message(
+        &mut self,
+        instance_id: &str,
+        filter: bool,
+    ) -> Option<crate::Message> {
+        let matcher = self.matcher.as_mut()?;
+        let search = self.search.as_ref()?;
+        let entries = if filter {
+            matcher.search(&search.query, None, &crate::frecency::scores(instance_id))
+        } else {
+            matcher.entries().to_vec()
+        };
+        Some(crate::Message::UpdateEntries(
+            String::from(instance_id),
+            search.generation,
+            entries,
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl Plugin for ScriptPlugin {
+    fn id() -> &'static str {
+        "script"
+    }
+
+    fn priority() -> u32 {
+        0
+    }
+
+    fn title() -> &'static str {
+        "󰆍 Script"
+    }
+
+    fn enable_by_default() -> bool {
+        false
+    }
+
+    fn new() -> Self {
+        Self::from_settings(settings::ScriptPluginSettings::default())
+    }
+
+    fn entries(&self) -> Vec<crate::model::Entry> {
+        self.state()
+            .matcher
+            .as_ref()
+            .map(|matcher| matcher.entries().to_vec())
+            .unwrap_or_default()
+    }
+
+    fn set_entries(&mut self, entries: Vec<crate::model::Entry>) {
+        self.state().matcher = Some(crate::plugin::matcher::EntryMatcher::new(entries));
+    }
+
+    fn update_interval(&self) -> Option<std::time::Duration> {
+        self.settings
+            .update_interval
+            .map(std::time::Duration::from_secs)
+    }
+
+    /// Starts the script, a failure is shown as status and requests fail until the script runs.
+    fn start(
+        &mut self,
+        instance: &crate::plugin::registry::PluginInstance,
+        plugin_channel_out: &mut async_channel::Sender<crate::Message>,
+        state: &mut crate::plugin::utils::PluginState,
+    ) -> anyhow::Result<()> {
+        if let Err(error) = self.spawn_script(instance, plugin_channel_out.clone()) {
+            log::error!(target: Self::id(), "{:?}", error);
+            self.report_status(instance, plugin_channel_out, state, Some(error.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Asks the script to update its entries, it sends them once they are updated.
+    fn update_entries(&mut self) -> anyhow::Result<()> {
+        self.send(ScriptRequest::Timeout)
+    }
+
+    fn activate(
+        &mut self,
+        entry: crate::model::Entry,
+        _plugin_channel_out: &mut async_channel::Sender<crate::Message>,
+    ) -> anyhow::Result<()> {
+        self.send(ScriptRequest::Activate {
+            entry: entry.into(),
+            action: None,
+        })
+    }
+
+    fn activate_action(
+        &mut self,
+        entry: crate::model::Entry,
+        action_id: &str,
+        _plugin_channel_out: &mut async_channel::Sender<crate::Message>,
+    ) -> anyhow::Result<()> {
+        self.send(ScriptRequest::Activate {
+            entry: entry.into(),
+            action: Some(action_id),
+        })
+    }
+
+    fn search(
+        &mut self,
+        instance: &crate::plugin::registry::PluginInstance,
+        search: &crate::model::Search,
+        state: &mut crate::plugin::utils::PluginState,
+        plugin_channel_out: &mut async_channel::Sender<crate::Message>,
+    ) -> anyhow::Result<()> {
+        let (message_option, already_sent) = {
+            let mut script_state = self.state();
+            // The search is repeated after every update, the script got it already
+            let already_sent = script_state
+                .search
+                .as_ref()
+                .is_some_and(|sent_search| sent_search.generation == search.generation);
+            script_state.search = Some(search.clone());
+            // Scripts filtered by centerpiece do not need to answer a search
+            let message_option = self
+                .settings
+                .filter
+                .then(|| script_state.update_entries_message(&instance.id, true))
+                .flatten();
+            (message_option, already_sent)
+        };
+        if let Some(message) = message_option {
+            plugin_channel_out.send_blocking(message).context(format!(
+                "Failed to send message to update entries while searching for '{}'.",
+                search.query
+            ))?;
+        }
+
+        if already_sent {
+            return Ok(());
+        }
+        // A script which stopped is shown as status, the plugin keeps running
+        let send_result = self.send(ScriptRequest::Search {
+            query: &search.query,
+            generation: search.generation,
+        });
+        if let Err(error) = send_result {
+            log::warn!(target: Self::id(), "{:?}", error);
+            self.report_status(instance, plugin_channel_out, state, Some(error.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+impl ConfigurablePlugin for ScriptPlugin {
+    type Settings = settings::ScriptPluginSettings;
+
+    fn from_settings(settings: Self::Settings) -> Self {
+        Self {
+            settings,
+            instance_id: String::new(),
+            stdin: None,
+            state: Arc::new(Mutex::new(ScriptState::default())),
+        }
+    }
+}
+
+impl Drop for ScriptPlugin {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl ScriptPlugin {
+    fn state(&self) -> std::sync::MutexGuard<'_, ScriptState> {
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Spawns the script and a thread forwarding its messages to the app.
+    fn spawn_script(
+        &mut self,
+        instance: &crate::plugin::registry::PluginInstance,
+        plugin_channel_out: async_channel::Sender<crate::Message>,
+    ) -> anyhow::Result<()> {
+        self.stop();
+        self.instance_id = instance.id.clone();
+        let (program, args) = self.settings.command.split_first().context(format!(
+            "No command configured for script plugin with id '{}'.",
+            self.instance_id
+        ))?;
+
+        let mut child = std::process::Command::new(program)
+            .args(args)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .context(format!(
+                "Failed to start script plugin with id '{}'.",
+                self.instance_id
+            ))?;
+        self.stdin = child.stdin.take();
+        let stdout = child
+            .stdout
+            .take()
+            .context("Failed to capture stdout of script plugin.")?;
+        let pid = child.id();
+        self.state().child = Some(child);
+
+        let instance_id = self.instance_id.clone();
+        let filter = self.settings.filter;
+        let state = self.state.clone();
+        std::thread::spawn(move || {
+            for line in std::io::BufReader::new(stdout)
+                .lines()
+                .map_while(Result::ok)
+            {
+                let message = match serde_json::from_str::<ScriptMessage>(&line) {
+                    Ok(message) => message,
+                    Err(error) => {
+                        log::warn!(
+                            target: Self::id(),
+                            "Ignoring invalid message of script plugin with id '{}': {}", instance_id, error
+                        );
+                        continue;
+                    }
+                };
+
+                let message_option = match message {
+                    ScriptMessage::UpdateEntries {
+                        entries,
+                        generation,
+                    } => {
+                        let mut state = state.lock().unwrap_or_else(|error| error.into_inner());
+                        // A slow answer to an earlier search must not replace the current entries
+                        let current_generation =
+                            state.search.as_ref().map(|search| search.generation);
+                        if !filter
+                            && generation
+                                .is_some_and(|generation| Some(generation) != current_generation)
+                        {
+                            continue;
+                        }
+                        state.matcher = Some(crate::plugin::matcher::EntryMatcher::new(
+                            entries.into_iter().map(Into::into).collect(),
+                        ));
+                        state.update_entries_message(&instance_id, filter)
+                    }
+                    ScriptMessage::Exit => Some(crate::Message::Exit),
+                };
+                let Some(message) = message_option else {
+                    continue;
+                };
+
+                if plugin_channel_out.send_blocking(message).is_err() {
+                    break;
+                }
+            }
+
+            log::info!(
+                target: Self::id(),
+                "Script plugin with id '{}' closed its stdout.", instance_id
+            );
+            // A script which closed its stdout cannot answer anymore, e.g. because it crashed
+            let child = {
+                let mut state = state.lock().unwrap_or_else(|error| error.into_inner());
+                match &state.child {
+                    Some(child) if child.id() == pid => state.child.take(),
+                    _ => None,
+                }
+            };
+            if let Some(child) = child {
+                stop(child);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Stops the running script, e.g. when the plugin instance is stopped.
+    fn stop(&mut self) {
+        self.stdin = None;
+        let child = self.state().child.take();
+        if let Some(child) = child {
+            stop(child);
+        }
+    }
+
+    fn send(&mut self, request: ScriptRequest) -> anyhow::Result<()> {
+        let stdin = self
+            .stdin
+            .as_mut()
+            .context("Script plugin is not running.")?;
+        let line = serde_json::to_string(&request).context("Failed to serialize request.")?;
+        writeln!(stdin, "{line}")
+            .and_then(|_| stdin.flush())
+            .context(format!(
+                "Failed to send request to script plugin with id '{}'.",
+                self.instance_id
+            ))
+    }
+}
+
+/// Kills the script and waits for it to exit, so it does not stay behind as a zombie process.
+fn stop(mut child: std::process::Child) {
+    // Killing fails if the script exited already, waiting reaps it either way
+    let _ = child.kill();
+    let _ = child.wait();
+}
@@ -2,22 +2,42 @@ use crate::plugin::utils::Plugin;
 use anyhow::Context;
 
 pub struct SwayWindowsPlugin {
-    sway: swayipc::Connection,
+    /// Connected on the first update, so a missing sway is reported as status
+    sway: Option<swayipc::Connection>,
     entries: Vec<crate::model::Entry>,
 }
 
 impl SwayWindowsPlugin {
-    fn get_window_nodes(node: swayipc::Node) -> Vec<swayipc::Node> {
+    fn sway(&mut self) -> anyhow::Result<&mut swayipc::Connection> {
+        let sway = match self.sway.take() {
+            Some(sway) => sway,
+            None => {
+                swayipc::Connection::new().context("Failed to establish sway ipc connection.")?
+            }
+        };
+        Ok(self.sway.insert(sway))
+    }
+
+    /// The windows below the node together with the name of their workspace.
+    fn get_window_nodes(
+        node: swayipc::Node,
+        workspace: Option<String>,
+    ) -> Vec<(Option<String>, swayipc::Node)> {
+        let workspace = match node.node_type {
+            swayipc::NodeType::Workspace => node.name.clone(),
+            _ => workspace,
+        };
+
         if !node.nodes.is_empty() {
             return node
                 .nodes
                 .into_iter()
-                .flat_map(Self::get_window_nodes)
+                .flat_map(|child| Self::get_window_nodes(child, workspace.clone()))
                 .collect();
         }
 
         if node.node_type == swayipc::NodeType::Con {
-            return vec![node];
+            return vec![(workspace, node)];
         }
 
         vec![]
@@ -26,7 +46,7 @@ impl SwayWindowsPlugin {
 
 impl Plugin for SwayWindowsPlugin {
     fn id() -> &'static str {
-        "sway-windows"
+        "sway_windows"
     }
     fn priority() -> u32 {
         30
@@ -34,6 +54,13 @@ impl Plugin for SwayWindowsPlugin {
     fn title() -> &'static str {
         "󰖯 Windows"
     }
+    fn update_on_show() -> bool {
+        true
+    }
+
+    fn default_keywords() -> &'static [&'static str] {
+        &["w"]
+    }
 
     fn entries(&self) -> Vec<crate::model::Entry> {
         self.entries.clone()
@@ -44,37 +71,38 @@ impl Plugin for SwayWindowsPlugin {
     }
 
     fn new() -> Self {
-        let connection_result =
-            swayipc::Connection::new().context("Failed to establish sway ipc connection.");
-        if let Err(error) = connection_result {
-            log::error!(target: Self::id(), "{:?}", error);
-            panic!();
-        }
-        let sway = connection_result.unwrap();
-
         Self {
-            sway,
+            sway: None,
             entries: vec![],
         }
     }
 
     fn update_entries(&mut self) -> anyhow::Result<()> {
-        let root_node_result = self
-            .sway
-            .get_tree()
-            .context("Failed to get_tree from sway ipc.");
-        if let Err(error) = root_node_result {
-            log::error!(target: Self::id(), "{:?}", error);
-            panic!();
+        // Stay empty instead of reporting an error when running under another compositor
+        if std::env::var_os("SWAYSOCK").is_none() {
+            self.entries.clear();
+            return Ok(());
+        }
+
+        let sway_root_node_result = self.sway()?.get_tree();
+        if sway_root_node_result.is_err() {
+            // The connection is reestablished on the next update, e.g. after sway restarted
+            self.sway = None;
         }
-        let sway_root_node = root_node_result.unwrap();
+        let sway_root_node = sway_root_node_result.context("Failed to get_tree from sway ipc.")?;
 
-        let entries: Vec<crate::model::Entry> = Self::get_window_nodes(sway_root_node)
+        let entries: Vec<crate::model::Entry> = Self::get_window_nodes(sway_root_node, None)
             .into_iter()
-            .map(|node| {
+            .map(|(workspace, node)| {
                 let name = node
                     .name
                     .unwrap_or(String::from("-- window name missing --"));
+                // Xwayland windows have a class instead of an app id
+                let icon = node.app_id.clone().or_else(|| {
+                    node.window_properties
+                        .as_ref()
+                        .and_then(|properties| properties.class.clone())
+                });
                 let app_id = node
                     .app_id
                     .unwrap_or(String::from("-- window app_id missing --"));
@@ -82,9 +110,22 @@ impl Plugin for SwayWindowsPlugin {
                 crate::model::Entry {
                     id: node.id.to_string(),
                     title,
+                    subtitle: workspace.map(|workspace| format!("workspace {workspace}")),
                     action: String::from("focus"),
                     meta: String::from("Sway Windows"),
                     command: None,
+                    actions: vec![
+                        crate::model::Action {
+                            id: String::from("close"),
+                            title: String::from("close"),
+                        },
+                        crate::model::Action {
+                            id: String::from("move-here"),
+                            title: String::from("move to current workspace"),
+                        },
+                    ],
+                    icon,
+                    ..Default::default()
                 }
             })
             .collect();
@@ -100,7 +141,7 @@ impl Plugin for SwayWindowsPlugin {
         entry: crate::model::Entry,
         plugin_channel_out: &mut async_channel::Sender<crate::Message>,
     ) -> anyhow::Result<()> {
-        self.sway
+        self.sway()?
             .run_command(format!("[con_id={}] focus", entry.id))
             .context(format!(
                 "Failed to focus window while activating entry with id '{}'.",
@@ -116,4 +157,48 @@ impl Plugin for SwayWindowsPlugin {
 
         Ok(())
     }
+
+    fn activate_action(
+        &mut self,
+        entry: crate::model::Entry,
+        action_id: &str,
+        plugin_channel_out: &mut async_channel::Sender<crate::Message>,
+    ) -> anyhow::Result<()> {
+        let command = match action_id {
+            "close" => format!("[con_id={}] kill", entry.id),
+            "move-here" => {
+                let workspace = self
+                    .sway()?
+                    .get_workspaces()
+                    .context("Failed to get_workspaces from sway ipc.")?
+                    .into_iter()
+                    .find(|workspace| workspace.focused)
+                    .context("Failed to find the focused sway workspace.")?;
+                format!(
+                    "[con_id={id}] move container to workspace \"{}\"; [con_id={id}] focus",
+                    workspace.name,
+                    id = entry.id
+                )
+            }
+            _ => anyhow::bail!(
+                "Unknown action '{}' of entry with id '{}'.",
+                action_id,
+                entry.id
+            ),
+        };
+
+        self.sway()?.run_command(command).context(format!(
+            "Failed to run action '{}' while activating entry with id '{}'.",
+            action_id, entry.id
+        ))?;
+
+        plugin_channel_out
+            .try_send(crate::Message::Exit)
+            .context(format!(
+                "Failed to send message to exit application while activating entry with id '{}'.",
+                entry.id
+            ))?;
+
+        Ok(())
+    }
 }
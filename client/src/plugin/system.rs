@@ -33,6 +33,7 @@ impl Plugin for SystemPlugin {
                 action: String::from(""),
                 meta: String::from("System"),
                 command: Some(vec![String::from("lock")]),
+                ..Default::default()
             },
             crate::model::Entry {
                 id: String::from("restart"),
@@ -40,6 +41,7 @@ impl Plugin for SystemPlugin {
                 action: String::from(""),
                 meta: String::from("System"),
                 command: Some(vec![String::from("reboot")]),
+                ..Default::default()
             },
             crate::model::Entry {
                 id: String::from("shutdown"),
@@ -47,6 +49,7 @@ impl Plugin for SystemPlugin {
                 action: String::from(""),
                 meta: String::from("System"),
                 command: Some(vec![String::from("poweroff")]),
+                ..Default::default()
             },
             crate::model::Entry {
                 id: String::from("sleep"),
@@ -54,6 +57,7 @@ impl Plugin for SystemPlugin {
                 action: String::from(""),
                 meta: String::from("System Hibernate Suspend"),
                 command: Some(vec![String::from("systemctl"), String::from("suspend")]),
+                ..Default::default()
             },
         ];
 
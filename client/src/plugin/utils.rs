@@ -1,25 +1,29 @@
 use anyhow::Context;
-use nucleo_matcher::{
-    Matcher, Utf32Str,
-    pattern::{Atom, AtomKind, CaseMatching, Normalization},
-};
-use std::cmp::Reverse;
-
-pub fn spawn<PluginType: Plugin + std::marker::Send + 'static>()
--> async_channel::Receiver<crate::Message> {
+use std::time::{Duration, Instant};
+
+/// Delay before updating the entries of a plugin is retried after the first failure.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// The retry delay doubles with every failure up to this delay.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Constructs the plugin with the given function in a new plugin thread and runs it.
+pub fn spawn<PluginType: Plugin + std::marker::Send + 'static>(
+    instance: crate::plugin::registry::PluginInstance,
+    new: impl FnOnce() -> PluginType + std::marker::Send + 'static,
+) -> async_channel::Receiver<crate::Message> {
     let (plugin_channel_out, app_channel_in) = async_channel::bounded(100);
 
     std::thread::spawn(move || {
         smol::block_on(async {
-            let mut plugin = PluginType::new();
+            let mut plugin = new();
 
-            let main_loop_result = plugin.main(plugin_channel_out).await;
+            // Plugins only stop on errors they cannot recover from, e.g. when the app is gone
+            let main_loop_result = plugin.main(&instance, plugin_channel_out).await;
             if let Err(error) = main_loop_result {
                 log::error!(
                     target: PluginType::id(),
-                    "{:?}", error,
+                    "Plugin instance '{}' stopped: {:?}", instance.id, error,
                 );
-                panic!();
             }
         });
     });
@@ -27,58 +31,77 @@ pub fn spawn<PluginType: Plugin + std::marker::Send + 'static>()
     app_channel_in
 }
 
-/// Fuzzy matches against the title of the entry, falling back to substring matching
-/// against the meta of the entry if no match is found in the title.
-fn fuzzy_match(query: &str, entries: Vec<crate::model::Entry>) -> Vec<crate::model::Entry> {
-    let mut matcher_config = nucleo_matcher::Config::DEFAULT;
-    matcher_config.prefer_prefix = true; // Higher score to matches earlier in the string
-    let mut fuzzy_matcher = Matcher::new(matcher_config);
-    let fuzzy_atom = Atom::new(
-        query,
-        CaseMatching::Ignore,
-        Normalization::Smart,
-        AtomKind::Fuzzy,
-        false,
-    );
-
-    let mut substring_matcher = Matcher::new(nucleo_matcher::Config::DEFAULT);
-    let substring_atom = Atom::new(
-        query,
-        CaseMatching::Ignore,
-        Normalization::Smart,
-        AtomKind::Substring,
-        true,
-    );
-
-    let mut buf = Vec::new();
-    let mut filtered_entries = entries
-        .into_iter()
-        .flat_map(|entry| {
-            // Attempt to fuzzy match against the title
-            fuzzy_atom
-                .score(
-                    Utf32Str::new(entry.title.as_ref(), &mut buf),
-                    &mut fuzzy_matcher,
-                )
-                .map(|score| score + 1000) // Always prefer title matches
-                // Fallback to substring match against the meta
-                .or_else(|| {
-                    substring_atom.score(
-                        Utf32Str::new(entry.meta.as_ref(), &mut buf),
-                        &mut substring_matcher,
-                    )
-                })
-                .map(|score| (score, entry))
-        })
-        .collect::<Vec<_>>();
-
-    // Sort by score
-    filtered_entries.sort_by_key(|(score, _entry)| Reverse(*score));
-
-    filtered_entries
-        .into_iter()
-        .map(|(_, entry)| entry)
-        .collect::<Vec<_>>()
+/// Forwards the messages of a plugin to the returned channel, calling `on_message` after each
+/// message, e.g. to wake up the event loop of the window.
+///
+/// Forwarding stops once the plugin stops or the returned channel is dropped.
+pub fn notify_on_message(
+    plugin_channel: async_channel::Receiver<crate::Message>,
+    on_message: std::sync::Arc<dyn Fn() + Send + Sync>,
+) -> async_channel::Receiver<crate::Message> {
+    let (forward_out, forward_in) = async_channel::bounded(100);
+
+    std::thread::spawn(move || {
+        while let Ok(message) = plugin_channel.recv_blocking() {
+            if forward_out.send_blocking(message).is_err() {
+                return;
+            }
+            on_message();
+        }
+    });
+
+    forward_in
+}
+
+/// Bookkeeping of the main loop of a plugin.
+#[derive(Debug, Default)]
+pub struct PluginState {
+    last_search: crate::model::Search,
+    /// Requests received while looking for later searches, handled before waiting for more
+    pending_requests: std::collections::VecDeque<crate::model::PluginRequest>,
+    /// The failure currently shown in place of the entries of the plugin
+    status: Option<String>,
+    /// When to retry updating the entries after it failed, and the delay used for that retry
+    retry: Option<(Instant, Duration)>,
+    /// The entries prepared for searching, prepared again after the entries changed
+    matcher: Option<crate::plugin::matcher::EntryMatcher>,
+}
+
+impl PluginState {
+    /// Waits for the next request, or sends a timeout request at `timeout_at`.
+    ///
+    /// A search is skipped if a later search already waits in the channel, so a plugin which
+    /// searches slower than the user types only searches the latest query.
+    pub async fn next_request(
+        &mut self,
+        plugin_channel_in: &async_channel::Receiver<crate::model::PluginRequest>,
+        timeout_at: Option<Instant>,
+    ) -> Option<crate::model::PluginRequest> {
+        let mut plugin_request = match self.pending_requests.pop_front() {
+            Some(plugin_request) => plugin_request,
+            None => match timeout_at {
+                Some(timeout_at) => {
+                    futures_lite::future::or(async { plugin_channel_in.recv().await.ok() }, async {
+                        smol::Timer::at(timeout_at).await;
+                        Some(crate::model::PluginRequest::Timeout)
+                    })
+                    .await?
+                }
+                None => plugin_channel_in.recv().await.ok()?,
+            },
+        };
+        if !matches!(plugin_request, crate::model::PluginRequest::Search(_)) {
+            return Some(plugin_request);
+        }
+
+        while let Ok(next_request) = plugin_channel_in.try_recv() {
+            match next_request {
+                crate::model::PluginRequest::Search(_) => plugin_request = next_request,
+                next_request => self.pending_requests.push_back(next_request),
+            }
+        }
+        Some(plugin_request)
+    }
 }
 
 #[async_trait::async_trait]
@@ -89,6 +112,19 @@ pub trait Plugin {
     fn update_timeout() -> Option<std::time::Duration> {
         None
     }
+    /// Whether the entries go stale while the window of a daemon is hidden, e.g. the open
+    /// windows, they are updated whenever the window is shown.
+    fn update_on_show() -> bool {
+        Self::update_timeout().is_some()
+    }
+    /// Whether the plugin runs if the config does not enable or disable it.
+    fn enable_by_default() -> bool {
+        true
+    }
+    /// Keywords scoping a search to the plugin if the config does not set any.
+    fn default_keywords() -> &'static [&'static str] {
+        &[]
+    }
 
     fn new() -> Self;
 
@@ -100,46 +136,121 @@ pub trait Plugin {
         Ok(())
     }
 
+    /// How often the entries are updated, [`Plugin::update_timeout`] unless the plugin
+    /// instance configures it.
+    fn update_interval(&self) -> Option<Duration> {
+        Self::update_timeout()
+    }
+
+    /// Runs once the plugin is registered, before the first request is handled.
+    fn start(
+        &mut self,
+        instance: &crate::plugin::registry::PluginInstance,
+        plugin_channel_out: &mut async_channel::Sender<crate::Message>,
+        state: &mut PluginState,
+    ) -> anyhow::Result<()> {
+        self.try_update_entries(instance, plugin_channel_out, state)
+    }
+
     fn plugin(
         &self,
+        instance: &crate::plugin::registry::PluginInstance,
         app_channel_out: &mut async_channel::Sender<crate::model::PluginRequest>,
     ) -> crate::model::Plugin {
         crate::model::Plugin {
-            id: String::from(Self::id()),
-            priority: Self::priority(),
-            title: String::from(Self::title()),
+            id: instance.id.clone(),
+            priority: instance.priority,
+            title: instance.title.clone(),
             app_channel_out: app_channel_out.clone(),
             entries: self.entries(),
+            status: None,
+            generation: 0,
+            update_on_show: Self::update_on_show(),
         }
     }
 
     async fn main(
         &mut self,
+        instance: &crate::plugin::registry::PluginInstance,
         mut plugin_channel_out: async_channel::Sender<crate::Message>,
     ) -> anyhow::Result<()> {
-        self.update_entries()?;
-
+        // Registering first lets the app show failures of the first update
         let (mut app_channel_out, mut plugin_channel_in) = async_channel::bounded(100);
-        self.register_plugin(&mut plugin_channel_out, &mut app_channel_out)?;
-        let mut last_query = String::from("");
+        self.register_plugin(instance, &mut plugin_channel_out, &mut app_channel_out)?;
+        // Only the app sends requests, the channel closes once the app stops the plugin
+        drop(app_channel_out);
 
-        loop {
+        let mut state = PluginState::default();
+        self.start(instance, &mut plugin_channel_out, &mut state)?;
+
+        while !plugin_channel_in.is_closed() {
             self.update(
+                instance,
                 &mut plugin_channel_out,
                 &mut plugin_channel_in,
-                &mut last_query,
+                &mut state,
             )
             .await?;
         }
+        Ok(())
+    }
+
+    /// Updates the entries, reporting a failure as status and scheduling a retry with backoff.
+    fn try_update_entries(
+        &mut self,
+        instance: &crate::plugin::registry::PluginInstance,
+        plugin_channel_out: &mut async_channel::Sender<crate::Message>,
+        state: &mut PluginState,
+    ) -> anyhow::Result<()> {
+        let update_result = self.update_entries();
+        state.matcher = None;
+        match update_result {
+            Ok(()) => {
+                state.retry = None;
+                self.report_status(instance, plugin_channel_out, state, None)
+            }
+            Err(error) => {
+                log::warn!(target: Self::id(), "{:?}", error);
+                let delay = state
+                    .retry
+                    .map_or(INITIAL_RETRY_DELAY, |(_, delay)| delay * 2)
+                    .min(MAX_RETRY_DELAY);
+                state.retry = Some((Instant::now() + delay, delay));
+                self.report_status(instance, plugin_channel_out, state, Some(error.to_string()))
+            }
+        }
+    }
+
+    /// Sends the status to the app if it changed.
+    fn report_status(
+        &self,
+        instance: &crate::plugin::registry::PluginInstance,
+        plugin_channel_out: &mut async_channel::Sender<crate::Message>,
+        state: &mut PluginState,
+        status: Option<String>,
+    ) -> anyhow::Result<()> {
+        if state.status == status {
+            return Ok(());
+        }
+        state.status = status.clone();
+
+        plugin_channel_out
+            .send_blocking(crate::Message::UpdateStatus(instance.id.clone(), status))
+            .context("Failed to send message to update plugin status.")?;
+
+        Ok(())
     }
 
     fn register_plugin(
         &mut self,
+        instance: &crate::plugin::registry::PluginInstance,
         plugin_channel_out: &mut async_channel::Sender<crate::Message>,
         app_channel_out: &mut async_channel::Sender<crate::model::PluginRequest>,
     ) -> anyhow::Result<()> {
         plugin_channel_out
-            .send_blocking(crate::Message::RegisterPlugin(self.plugin(app_channel_out)))
+            .send_blocking(crate::Message::RegisterPlugin(
+                self.plugin(instance, app_channel_out),
+            ))
             .context("Failed to send message to register plugin.")?;
 
         Ok(())
@@ -147,36 +258,55 @@ pub trait Plugin {
 
     async fn update(
         &mut self,
+        instance: &crate::plugin::registry::PluginInstance,
         plugin_channel_out: &mut async_channel::Sender<crate::Message>,
         plugin_channel_in: &mut async_channel::Receiver<crate::model::PluginRequest>,
-        last_query: &mut String,
+        state: &mut PluginState,
     ) -> anyhow::Result<()> {
-        let plugin_request_option = match Self::update_timeout() {
-            Some(update_timeout) => {
-                futures_lite::future::or(async { plugin_channel_in.recv().await.ok() }, async {
-                    smol::Timer::after(update_timeout).await;
-                    Some(crate::model::PluginRequest::Timeout)
-                })
-                .await
-            }
-            None => plugin_channel_in.recv().await.ok(),
+        let update_at = self
+            .update_interval()
+            .map(|update_interval| Instant::now() + update_interval);
+        let timeout_at = match (update_at, state.retry) {
+            (Some(update_at), Some((retry_at, _))) => Some(update_at.min(retry_at)),
+            (update_at, retry) => update_at.or(retry.map(|(retry_at, _)| retry_at)),
         };
-        if plugin_request_option.is_none() {
+        let Some(plugin_request) = state.next_request(plugin_channel_in, timeout_at).await else {
             return Ok(());
-        }
-        let plugin_request = plugin_request_option.unwrap();
+        };
 
         match plugin_request {
-            crate::model::PluginRequest::Search(query) => {
-                self.search(&query, plugin_channel_out)?;
-                *last_query = query;
+            crate::model::PluginRequest::Search(search) => {
+                self.search(instance, &search, state, plugin_channel_out)?;
+                state.last_search = search;
             }
             crate::model::PluginRequest::Timeout => {
-                self.update_entries()?;
-                self.search(last_query, plugin_channel_out)?;
+                self.try_update_entries(instance, plugin_channel_out, state)?;
+                let last_search = state.last_search.clone();
+                self.search(instance, &last_search, state, plugin_channel_out)?;
             }
-            crate::model::PluginRequest::Activate(entry) => {
-                self.activate(entry, plugin_channel_out)?
+            crate::model::PluginRequest::Activate(entry, action_id) => {
+                let activate_result = match action_id {
+                    None => self.activate(*entry, plugin_channel_out),
+                    Some(action_id) => self.activate_action(*entry, &action_id, plugin_channel_out),
+                };
+                // Activating an entry may change the entries, e.g. the state of a connection
+                state.matcher = None;
+                // A failed activation must not stop the plugin, the app may be kept open
+                match activate_result {
+                    Ok(()) if state.retry.is_none() => {
+                        self.report_status(instance, plugin_channel_out, state, None)?
+                    }
+                    Ok(()) => {}
+                    Err(error) => {
+                        log::error!(target: Self::id(), "{:?}", error);
+                        self.report_status(
+                            instance,
+                            plugin_channel_out,
+                            state,
+                            Some(error.to_string()),
+                        )?;
+                    }
+                }
             }
         }
 
@@ -191,19 +321,27 @@ pub trait Plugin {
 
     fn search(
         &mut self,
-        query: &str,
+        instance: &crate::plugin::registry::PluginInstance,
+        search: &crate::model::Search,
+        state: &mut PluginState,
         plugin_channel_out: &mut async_channel::Sender<crate::Message>,
     ) -> anyhow::Result<()> {
-        let filtered_entries = fuzzy_match(query, self.entries());
+        let frecency_scores = crate::frecency::scores(&instance.id);
+        let matcher = state
+            .matcher
+            .get_or_insert_with(|| crate::plugin::matcher::EntryMatcher::new(self.entries()));
+        let filtered_entries =
+            matcher.search(&search.query, instance.max_results, &frecency_scores);
 
         plugin_channel_out
             .send_blocking(crate::Message::UpdateEntries(
-                String::from(Self::id()),
+                instance.id.clone(),
+                search.generation,
                 filtered_entries,
             ))
             .context(format!(
                 "Failed to send message to update entries while searching for '{}'.",
-                query
+                search.query
             ))?;
 
         Ok(())
@@ -216,6 +354,27 @@ pub trait Plugin {
     ) -> anyhow::Result<()> {
         Ok(())
     }
+
+    /// Runs one of the additional actions of an entry.
+    fn activate_action(
+        &mut self,
+        entry: crate::model::Entry,
+        action_id: &str,
+        _plugin_channel_out: &mut async_channel::Sender<crate::Message>,
+    ) -> anyhow::Result<()> {
+        log::warn!(
+            target: Self::id(),
+            "Ignoring unknown action '{}' of entry with id '{}'.", action_id, entry.id
+        );
+        Ok(())
+    }
+}
+
+/// Plugins with settings of their own, read from the config section of each plugin instance.
+pub trait ConfigurablePlugin: Plugin {
+    type Settings: serde::de::DeserializeOwned + std::marker::Send + 'static;
+
+    fn from_settings(settings: Self::Settings) -> Self;
 }
 
 pub fn read_index_file<T>(file_name: &str) -> anyhow::Result<T>
@@ -225,17 +384,8 @@ where
     let cache_directory = settings::centerpiece_cache_directory()?;
     let index_file_path = format!("{cache_directory}/{file_name}");
 
-    let index_file =
-        std::fs::File::open(index_file_path).context("Error while opening index file")?;
+    let index_file = std::fs::File::open(index_file_path).context("Index file missing")?;
 
     let reader = std::io::BufReader::new(index_file);
-    let git_repository_paths_result: Result<T, _> = serde_json::from_reader(reader);
-    if let Err(error) = git_repository_paths_result {
-        log::error!(
-            error = log::error!("{:?}", error);
-            "Error while reading index file",
-        );
-        panic!();
-    }
-    Ok(git_repository_paths_result.unwrap())
+    serde_json::from_reader(reader).context("Index file is invalid")
 }
@@ -33,7 +33,7 @@ impl WifiPlugin {
         wifi_device
             .request_scan(std::collections::HashMap::new())
             .map_err(|_| anyhow!("Failed to request scan for wifi access points."))?;
-        let mut access_points = wifi_device
+        let access_points = wifi_device
             .get_access_points()
             .map_err(|_| anyhow!("Failed to get access points from wifi device."))?;
 
@@ -42,21 +42,29 @@ impl WifiPlugin {
             Err(_) => String::new(),
         };
 
-        // dedup access points by name and sort by signal strength
-        access_points.sort_by_key(|access_point| access_point.strength().ok().unwrap());
-        access_points.reverse();
-        access_points.sort_by_key(|access_point| access_point.ssid().ok().unwrap());
-        access_points.dedup_by_key(|access_point| access_point.ssid().ok().unwrap());
-        access_points.sort_by_key(|access_point| access_point.strength().ok().unwrap());
-        access_points.reverse();
-
-        let wifi_network_entries: Vec<crate::model::Entry> = access_points
+        // Access points which disappeared while reading their properties are skipped
+        let mut networks: Vec<(String, u8, u32)> = access_points
             .into_iter()
             .filter_map(|access_point| {
-                let ssid = access_point.ssid().ok()?;
-                let strength = access_point.strength().ok()?;
+                Some((
+                    access_point.ssid().ok()?,
+                    access_point.strength().ok()?,
+                    access_point.rsn_flags().ok()?,
+                ))
+            })
+            .collect();
 
-                let strength_icon = match access_point.rsn_flags().ok()? {
+        // dedup access points by name keeping the strongest and sort by signal strength
+        networks.sort_by(|(ssid, strength, _), (other_ssid, other_strength, _)| {
+            ssid.cmp(other_ssid).then(other_strength.cmp(strength))
+        });
+        networks.dedup_by(|(ssid, _, _), (other_ssid, _, _)| ssid == other_ssid);
+        networks.sort_by_key(|(_, strength, _)| std::cmp::Reverse(*strength));
+
+        let wifi_network_entries: Vec<crate::model::Entry> = networks
+            .into_iter()
+            .map(|(ssid, strength, rsn_flags)| {
+                let strength_icon = match rsn_flags {
                     0 => match strength {
                         0..=20 => "󰤯",
                         21..=40 => "󰤟",
@@ -80,7 +88,7 @@ impl WifiPlugin {
                     false => String::new(),
                 };
 
-                Some(crate::model::Entry {
+                crate::model::Entry {
                     id: ssid.clone(),
                     title: format!("{}{} {}", strength_icon, connected_icon, ssid.clone()),
                     action: String::from("connect"),
@@ -92,7 +100,8 @@ impl WifiPlugin {
                         String::from("connect"),
                         ssid,
                     ]),
-                })
+                    ..Default::default()
+                }
             })
             .collect();
         Ok(wifi_network_entries)
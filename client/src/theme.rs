@@ -0,0 +1,136 @@
+use anyhow::Context;
+
+/// Colors and sizes of the window resolved from the color and theme settings.
+pub struct Theme {
+    pub text: egui::Color32,
+    pub background: egui::Color32,
+    pub accent: egui::Color32,
+    pub selection: egui::Color32,
+    /// Falls back to the weak text color of egui
+    pub muted: Option<egui::Color32>,
+    /// Falls back to the separator color of egui
+    pub separator: Option<egui::Color32>,
+    pub row_padding: f32,
+    pub corner_radius: f32,
+    pub row_corner_radius: f32,
+    /// The font size the sizes are based on, see [`rem`]
+    pub rem: f32,
+}
+
+impl Theme {
+    pub fn new(settings: &settings::Settings) -> Self {
+        let rem = settings.theme.font.size;
+        let color = |color: &Option<String>| color.as_deref().map(settings::hexcolor);
+        let text = settings::hexcolor(&settings.color.text);
+
+        Self {
+            text,
+            background: settings::hexcolor(&settings.color.background),
+            accent: color(&settings.color.accent).unwrap_or(text),
+            selection: color(&settings.color.selection).unwrap_or(text),
+            muted: color(&settings.color.muted),
+            separator: color(&settings.color.separator),
+            row_padding: settings.theme.row_padding.unwrap_or(0.5 * rem),
+            corner_radius: settings.theme.corner_radius.unwrap_or(0.5 * rem),
+            row_corner_radius: settings.theme.row_corner_radius.unwrap_or(0.1 * rem),
+            rem,
+        }
+    }
+
+    /// Height of the row of an entry, a subtitle adds a line of small text.
+    pub fn entry_height(&self, subtitle: bool) -> f32 {
+        let height = 1.3 * self.rem + 2. * self.row_padding;
+        if subtitle {
+            height + 1.1 * self.rem
+        } else {
+            height
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::new(&settings::Settings::default())
+    }
+}
+
+/// The unit of sizes and spacings, the configured font size.
+pub fn rem() -> f32 {
+    settings::Settings::get_or_init().theme.font.size
+}
+
+/// The bundled symbols font with the configured font in front of it.
+pub fn font_definitions() -> egui::FontDefinitions {
+    let mut fonts = egui::FontDefinitions::default();
+    fonts.font_data.insert(
+        "NerdFontSymbols".to_owned(),
+        std::sync::Arc::new(egui::FontData::from_static(include_bytes!(
+            "../assets/SymbolsNerdFontMono-Regular.ttf"
+        ))),
+    );
+    fonts
+        .families
+        .entry(egui::FontFamily::Monospace)
+        .or_default()
+        .push("NerdFontSymbols".to_owned());
+
+    let font_result = user_font();
+    if let Err(error) = &font_result {
+        log::warn!("{:?}", error);
+    }
+    if let Ok(Some(font)) = font_result {
+        fonts
+            .font_data
+            .insert("UserFont".to_owned(), std::sync::Arc::new(font));
+        for family in [egui::FontFamily::Monospace, egui::FontFamily::Proportional] {
+            fonts
+                .families
+                .entry(family)
+                .or_default()
+                .insert(0, "UserFont".to_owned());
+        }
+    }
+
+    fonts
+}
+
+/// Reads the configured font file, or the file of the configured font family.
+fn user_font() -> anyhow::Result<Option<egui::FontData>> {
+    let font_settings = &settings::Settings::get_or_init().theme.font;
+    let path = match (&font_settings.file, &font_settings.family) {
+        (Some(file), _) => file.clone(),
+        (None, Some(family)) => font_file(family)?,
+        (None, None) => return Ok(None),
+    };
+
+    let font = std::fs::read(&path).context(format!("Failed to read font file '{path}'."))?;
+    Ok(Some(egui::FontData::from_owned(font)))
+}
+
+fn font_file(family: &str) -> anyhow::Result<String> {
+    let output = std::process::Command::new("fc-match")
+        .args(["--format=%{family}\n%{file}", family])
+        .output()
+        .context(format!(
+            "Failed to look up font family '{family}', is fontconfig installed?"
+        ))?;
+    let output_text = String::from_utf8_lossy(&output.stdout);
+    let (matched_families, path) = output_text.split_once('\n').unwrap_or_default();
+    if !output.status.success() || path.is_empty() {
+        anyhow::bail!("Font family '{}' not found.", family);
+    }
+
+    // fc-match falls back to another font for unknown families, e.g. misspelled ones
+    let requested_family = family.split(':').next().unwrap_or_default().trim();
+    if !matched_families
+        .split(',')
+        .any(|matched_family| matched_family.trim().eq_ignore_ascii_case(requested_family))
+    {
+        anyhow::bail!(
+            "Font family '{}' not found, fontconfig suggests '{}' instead.",
+            family,
+            matched_families.split(',').next().unwrap_or_default()
+        );
+    }
+    Ok(String::from(path))
+}
@@ -0,0 +1,101 @@
+use smithay_client_toolkit::reexports::client::protocol::wl_output::WlOutput;
+use smithay_client_toolkit::shell::WaylandSurface;
+use smithay_client_toolkit::shell::wlr_layer::{
+    Anchor, KeyboardInteractivity, Layer, LayerSurface,
+};
+
+pub type Surface = wayapp::EguiSurfaceState<LayerSurface>;
+
+/// Creates the layer surface of the launcher with the configured geometry.
+pub fn create_surface(app: &wayapp::Application) -> Surface {
+    let settings = &settings::Settings::get_or_init().window;
+
+    let output = settings.output_name().and_then(|name| {
+        let output = app.output_state.outputs().find(|output| {
+            app.output_state
+                .info(output)
+                .is_some_and(|info| info.name.as_deref() == Some(name))
+        });
+        if output.is_none() {
+            log::warn!(
+                "Output '{}' not found, opening on the focused output.",
+                name
+            );
+        }
+        output
+    });
+
+    let layer = match settings.layer {
+        settings::window::Layer::Top => Layer::Top,
+        settings::window::Layer::Overlay => Layer::Overlay,
+    };
+    let layer_surface = app.layer_shell.create_layer_surface(
+        &app.qh,
+        app.compositor_state.create_surface(&app.qh),
+        layer,
+        Some(crate::APP_ID),
+        output.as_ref(),
+    );
+    layer_surface.set_keyboard_interactivity(KeyboardInteractivity::Exclusive);
+    layer_surface.set_anchor(
+        settings
+            .anchor
+            .iter()
+            .fold(Anchor::empty(), |anchor, edge| {
+                anchor
+                    | match edge {
+                        settings::window::Anchor::Top => Anchor::TOP,
+                        settings::window::Anchor::Bottom => Anchor::BOTTOM,
+                        settings::window::Anchor::Left => Anchor::LEFT,
+                        settings::window::Anchor::Right => Anchor::RIGHT,
+                    }
+            }),
+    );
+    let margin = settings.margin;
+    layer_surface.set_margin(margin.top, margin.right, margin.bottom, margin.left);
+
+    // The focused output is unknown until the surface is shown, the first output is the
+    // best guess for sizes relative to the output
+    let output_size = output
+        .or_else(|| app.output_state.outputs().next())
+        .and_then(|output| output_size(app, &output));
+    let (width, height) = settings.size(output_size);
+    layer_surface.set_size(width, height);
+    layer_surface.commit();
+
+    wayapp::EguiSurfaceState::new(app, layer_surface, width, height)
+}
+
+/// Resizes the surface relative to the output it is shown on once the compositor placed it.
+pub fn handle_events(
+    app: &wayapp::Application,
+    surface: &Surface,
+    events: &[wayapp::WaylandEvent],
+) {
+    let settings = &settings::Settings::get_or_init().window;
+    let relative = [settings.width, settings.height]
+        .iter()
+        .any(|length| matches!(length, settings::window::Length::Percent(_)));
+    if !relative {
+        return;
+    }
+
+    let layer_surface = surface.get_content();
+    for event in events {
+        let wayapp::WaylandEvent::SurfaceEnteredOutput(wl_surface, output) = event else {
+            continue;
+        };
+        if wl_surface != layer_surface.wl_surface() {
+            continue;
+        }
+
+        let (width, height) = settings.size(output_size(app, output));
+        layer_surface.set_size(width, height);
+        layer_surface.commit();
+    }
+}
+
+fn output_size(app: &wayapp::Application, output: &WlOutput) -> Option<(u32, u32)> {
+    let (width, height) = app.output_state.info(output)?.logical_size?;
+    Some((width.max(0) as u32, height.max(0) as u32))
+}
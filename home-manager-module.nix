@@ -8,6 +8,7 @@
 let
   cfg = config.programs.centerpiece;
   git-index-name = "index-git-repositories";
+  yamlFormat = pkgs.formats.yaml { };
 in
 {
   options.programs.centerpiece = {
@@ -26,192 +27,484 @@ in
           type = lib.types.str;
           description = lib.mdDoc "Background color within centerpiece.";
         };
-      };
 
-      plugin = {
-        applications = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
-          };
+        accent = lib.mkOption {
+          default = null;
+          type = lib.types.nullOr lib.types.str;
+          description = lib.mdDoc "Color of the characters matching the query, defaults to the text color.";
         };
 
-        brave_bookmarks = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
-          };
+        selection = lib.mkOption {
+          default = null;
+          type = lib.types.nullOr lib.types.str;
+          description = lib.mdDoc "Outline of the selected entry, defaults to the text color.";
         };
 
-        brave_history = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
-          };
+        muted = lib.mkOption {
+          default = null;
+          type = lib.types.nullOr lib.types.str;
+          description = lib.mdDoc "Color of secondary text like subtitles, defaults to a faded text color.";
         };
 
-        brave_progressive_web_apps = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
-          };
+        separator = lib.mkOption {
+          default = null;
+          type = lib.types.nullOr lib.types.str;
+          description = lib.mdDoc "Color of the lines between sections, defaults to a faded text color.";
         };
+      };
 
-        clock = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
-          };
+      layout = {
+        mode = lib.mkOption {
+          default = "grouped";
+          type = lib.types.enum [
+            "grouped"
+            "flat"
+          ];
+          description = lib.mdDoc ''
+            How entries are arranged.
+            `grouped` shows entries grouped by plugin, `flat` orders the entries of all plugins by how well they match the query.
+          '';
         };
 
-        firefox_bookmarks = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
+        plugin_weights = lib.mkOption {
+          default = { };
+          type = lib.types.attrsOf lib.types.int;
+          description = lib.mdDoc "Added to the match score of the entries of a plugin in the flat layout.";
+          example = {
+            applications = 100;
+            brave_history = -100;
           };
         };
+      };
 
-        firefox_history = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
-          };
+      icon = {
+        enable = lib.mkOption {
+          default = true;
+          type = lib.types.bool;
+          description = lib.mdDoc "Show icons next to applications, windows and progressive web apps.";
+        };
+
+        theme = lib.mkOption {
+          default = null;
+          type = lib.types.nullOr lib.types.str;
+          description = lib.mdDoc "Icon theme searched before the `hicolor` fallback theme.";
+          example = "Papirus";
         };
+      };
+
+      keybindings = lib.mkOption {
+        default = { };
+        type = lib.types.attrsOf lib.types.str;
+        description = lib.mdDoc ''
+          Commands by key chord, added to the default keybindings.
+          Bind a default chord to `none` to remove it.
+        '';
+        example = {
+          "ctrl+d" = "page_down";
+          "tab" = "none";
+        };
+      };
 
-        git_repositories = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
+      theme = {
+        font = {
+          family = lib.mkOption {
+            default = null;
+            type = lib.types.nullOr lib.types.str;
+            description = lib.mdDoc "Name of an installed font family, looked up with fontconfig.";
+            example = "JetBrains Mono";
           };
-          zoxide = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable zoxide integration.";
+
+          file = lib.mkOption {
+            default = null;
+            type = lib.types.nullOr lib.types.str;
+            description = lib.mdDoc "Path to a ttf or otf font file, takes precedence over the family.";
           };
-          commands = lib.mkOption {
-            default = [
-              [
-                "alacritty"
-                "--command"
-                "nvim"
-                "$GIT_DIRECTORY"
-              ]
-              [
-                "alacritty"
-                "--working-directory"
-                "$GIT_DIRECTORY"
-              ]
-            ];
-            type = lib.types.listOf (lib.types.listOf lib.types.str);
-            description = lib.mdDoc ''
-              The commands to launch when an entry is selected.
-              Use the $GIT_DIRECTORY variable to pass in the selected directory.
-              Use the $GIT_DIRECTORY_NAME variable to pass in the selected directory name.
-            '';
-            example = [
-              [
-                "code"
-                "--new-window"
-                "$GIT_DIRECTORY"
-              ]
-              [
-                "alacritty"
-                "--command"
-                "lazygit"
-                "--path"
-                "$GIT_DIRECTORY"
-              ]
-              [
-                "alacritty"
-                "--working-directory"
-                "$GIT_DIRECTORY"
-              ]
-            ];
+
+          size = lib.mkOption {
+            default = 14;
+            type = lib.types.number;
+            description = lib.mdDoc "Size of the text in logical pixels, spacings scale with it.";
           };
         };
 
-        gitmoji = {
-          enable = lib.mkOption {
-            default = false;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
-          };
+        row_padding = lib.mkOption {
+          default = null;
+          type = lib.types.nullOr lib.types.number;
+          description = lib.mdDoc "Space between the outline of an entry and its content in logical pixels, scales with the font size if unset.";
         };
 
-        resource_monitor_battery = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
-          };
+        corner_radius = lib.mkOption {
+          default = null;
+          type = lib.types.nullOr lib.types.number;
+          description = lib.mdDoc "Corner radius of the window in logical pixels, scales with the font size if unset.";
         };
 
-        resource_monitor_cpu = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
-          };
+        row_corner_radius = lib.mkOption {
+          default = null;
+          type = lib.types.nullOr lib.types.number;
+          description = lib.mdDoc "Corner radius of the outline of an entry in logical pixels, scales with the font size if unset.";
         };
+      };
 
-        resource_monitor_disks = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
-          };
+      window = {
+        width = lib.mkOption {
+          default = 800;
+          type = lib.types.either lib.types.ints.unsigned lib.types.str;
+          description = lib.mdDoc "Width of the window in logical pixels or as percentage of the output, e.g. `50%`.";
         };
 
-        resource_monitor_memory = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
-          };
+        height = lib.mkOption {
+          default = 600;
+          type = lib.types.either lib.types.ints.unsigned lib.types.str;
+          description = lib.mdDoc "Height of the window in logical pixels or as percentage of the output, e.g. `50%`.";
         };
 
-        niri_windows = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
-          };
+        anchor = lib.mkOption {
+          default = [ ];
+          type = lib.types.listOf (
+            lib.types.enum [
+              "top"
+              "bottom"
+              "left"
+              "right"
+            ]
+          );
+          description = lib.mdDoc "Edges of the output the window is attached to, the window is centered without anchors.";
+          example = [ "top" ];
         };
 
-        sway_windows = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
+        margin = {
+          top = lib.mkOption {
+            default = 0;
+            type = lib.types.int;
+            description = lib.mdDoc "Distance in logical pixels to the top edge of the output, if anchored to it.";
           };
-        };
 
-        system = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
+          right = lib.mkOption {
+            default = 0;
+            type = lib.types.int;
+            description = lib.mdDoc "Distance in logical pixels to the right edge of the output, if anchored to it.";
           };
+
+          bottom = lib.mkOption {
+            default = 0;
+            type = lib.types.int;
+            description = lib.mdDoc "Distance in logical pixels to the bottom edge of the output, if anchored to it.";
+          };
+
+          left = lib.mkOption {
+            default = 0;
+            type = lib.types.int;
+            description = lib.mdDoc "Distance in logical pixels to the left edge of the output, if anchored to it.";
+          };
+        };
+
+        layer = lib.mkOption {
+          default = "top";
+          type = lib.types.enum [
+            "top"
+            "overlay"
+          ];
+          description = lib.mdDoc "`top` shows the window above other windows, `overlay` also above fullscreen windows.";
         };
 
-        wifi = {
-          enable = lib.mkOption {
-            default = true;
-            type = lib.types.bool;
-            description = lib.mdDoc "Enable / disable the plugin.";
+        output = lib.mkOption {
+          default = "focused";
+          type = lib.types.str;
+          description = lib.mdDoc "Name of the output to open the window on, e.g. `DP-1`, or `focused` for the focused output.";
+        };
+      };
+
+      plugin = lib.mkOption {
+        default = { };
+        description = lib.mdDoc ''
+          Config sections of the plugin instances by instance id.
+          Sections with a `type` add further instances of a plugin, e.g. script plugins.
+        '';
+        type = lib.types.submodule {
+          freeformType = yamlFormat.type;
+          options = {
+            applications = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            brave_bookmarks = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ "b" ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            brave_history = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ "h" ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            brave_progressive_web_apps = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            clock = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            firefox_bookmarks = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ "b" ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            firefox_history = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ "h" ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            git_repositories = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ "g" ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+              zoxide = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable zoxide integration.";
+              };
+              commands = lib.mkOption {
+                default = [
+                  [
+                    "alacritty"
+                    "--command"
+                    "nvim"
+                    "$GIT_DIRECTORY"
+                  ]
+                  [
+                    "alacritty"
+                    "--working-directory"
+                    "$GIT_DIRECTORY"
+                  ]
+                ];
+                type = lib.types.listOf (lib.types.listOf lib.types.str);
+                description = lib.mdDoc ''
+                  The commands to launch when an entry is selected.
+                  Use the $GIT_DIRECTORY variable to pass in the selected directory.
+                  Use the $GIT_DIRECTORY_NAME variable to pass in the selected directory name.
+                '';
+                example = [
+                  [
+                    "code"
+                    "--new-window"
+                    "$GIT_DIRECTORY"
+                  ]
+                  [
+                    "alacritty"
+                    "--command"
+                    "lazygit"
+                    "--path"
+                    "$GIT_DIRECTORY"
+                  ]
+                  [
+                    "alacritty"
+                    "--working-directory"
+                    "$GIT_DIRECTORY"
+                  ]
+                ];
+              };
+            };
+
+            gitmoji = {
+              enable = lib.mkOption {
+                default = false;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            resource_monitor_battery = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            resource_monitor_cpu = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            resource_monitor_disks = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            resource_monitor_memory = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            niri_windows = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ "w" ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            sway_windows = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ "w" ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            system = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
+
+            wifi = {
+              enable = lib.mkOption {
+                default = true;
+                type = lib.types.bool;
+                description = lib.mdDoc "Enable / disable the plugin.";
+              };
+              keywords = lib.mkOption {
+                default = [ ];
+                type = lib.types.listOf lib.types.str;
+                description = lib.mdDoc "Typing one of these keywords followed by a space at the start of the query only searches this plugin.";
+              };
+            };
           };
         };
       };
     };
 
+    services.daemon = {
+      enable = lib.mkOption {
+        default = false;
+        type = lib.types.bool;
+        description = lib.mdDoc ''
+          Keep centerpiece running in the background.
+          Use `centerpiece toggle` to show and hide the window.
+        '';
+      };
+    };
+
     services.index-git-repositories = {
       enable = lib.mkOption {
         default = true;
@@ -239,6 +532,26 @@ in
       home.file.".config/centerpiece/config.yml".text = lib.generators.toYAML { } cfg.config;
     })
 
+    (lib.mkIf cfg.services.daemon.enable {
+      systemd.user.services.centerpiece-daemon = {
+        Unit = {
+          Description = "Centerpiece - your trusty omnibox search";
+          Documentation = "https://github.com/friedow/centerpiece";
+          PartOf = [ "graphical-session.target" ];
+          After = [ "graphical-session.target" ];
+        };
+
+        Service = {
+          ExecStart = "${lib.getExe centerpiece} --daemon";
+          Restart = "on-failure";
+        };
+
+        Install = {
+          WantedBy = [ "graphical-session.target" ];
+        };
+      };
+    })
+
     (lib.mkIf cfg.services.index-git-repositories.enable {
       systemd.user = {
         services = {
@@ -0,0 +1,325 @@
+use anyhow::Context;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Checks the config sections of the plugins, which only the app knows.
+static PLUGIN_CHECK: OnceLock<fn(&crate::Settings) -> Vec<Diagnostic>> = OnceLock::new();
+
+/// Sets the check of the config sections of the plugins, run with every check of a config file.
+pub fn set_plugin_check(plugin_check: fn(&crate::Settings) -> Vec<Diagnostic>) {
+    let _ = PLUGIN_CHECK.set(plugin_check);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found in the config file.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Dotted path of the offending key, e.g. `plugin.brave_history.enable`, empty if the
+    /// problem is not caused by a single key
+    pub key: String,
+    /// Line and column, both starting at 1, looked up from the key if unset
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            key: key.into(),
+            position: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            key: key.into(),
+            position: None,
+            message: message.into(),
+        }
+    }
+
+    fn from_yaml_error(error: &serde_yaml::Error) -> Self {
+        let message = error.to_string();
+        let position = error
+            .location()
+            .map(|location| (location.line(), location.column()));
+        // The position is shown in front of the message instead
+        let message = match position {
+            Some((line, column)) => {
+                message.replacen(&format!(" at line {line} column {column}"), "", 1)
+            }
+            None => message,
+        };
+        // serde_yaml prefixes the message with the path of the key
+        let (key, message) = match message.split_once(": ") {
+            Some((key, message)) if !key.contains(' ') => (key.to_string(), message.to_string()),
+            _ => (String::new(), message),
+        };
+
+        Self {
+            position,
+            ..Self::error(key, message)
+        }
+    }
+}
+
+/// The result of checking a config file.
+pub struct Check {
+    pub config_file_path: String,
+    /// The settings, `None` if the config file does not match the settings
+    pub settings: Option<crate::Settings>,
+    pub diagnostics: Vec<Diagnostic>,
+    key_positions: HashMap<String, (usize, usize)>,
+}
+
+impl Check {
+    /// Checks the config file for syntax errors, values not matching the settings, unknown
+    /// keys, invalid colors and deprecated keys.
+    ///
+    /// The config sections of plugins are checked by the plugin check if one is set, see
+    /// [`set_plugin_check`].
+    pub fn of(config_file_path: &str) -> anyhow::Result<Self> {
+        let mut check = Check {
+            config_file_path: String::from(config_file_path),
+            settings: None,
+            diagnostics: vec![],
+            key_positions: HashMap::new(),
+        };
+        let content = match std::fs::read_to_string(config_file_path) {
+            Ok(content) => content,
+            // The defaults are used without a config file
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                check.settings = Some(crate::Settings::default());
+                return Ok(check);
+            }
+            Err(error) => {
+                return Err(error)
+                    .context(format!("Failed to read config file '{config_file_path}'."));
+            }
+        };
+        check.key_positions = key_positions(&content);
+
+        let mut unknown_keys = vec![];
+        let settings_result: Result<crate::Settings, _> =
+            serde_ignored::deserialize(serde_yaml::Deserializer::from_str(&content), |path| {
+                unknown_keys.push(normalize_key(&path.to_string()))
+            });
+        let settings = match settings_result {
+            Ok(settings) => settings,
+            Err(error) => {
+                check.diagnostics.push(Diagnostic::from_yaml_error(&error));
+                return Ok(check);
+            }
+        };
+
+        for key in unknown_keys {
+            check
+                .diagnostics
+                .push(Diagnostic::warning(key, "Unknown key, it is ignored."));
+        }
+
+        for (key, color) in settings.color.colors() {
+            if egui::Color32::from_hex(color).is_err() {
+                check.diagnostics.push(Diagnostic::error(
+                    format!("color.{key}"),
+                    format!("'{color}' is not a valid color code, expected e.g. '#1e1e2e'."),
+                ));
+            }
+        }
+
+        if let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(&content) {
+            let keybinding_diagnostics = crate::keybindings::check(&value["keybindings"]);
+            check.diagnostics.extend(keybinding_diagnostics);
+        }
+
+        #[allow(deprecated)]
+        if settings.color.surface != *"deprecated" {
+            check.diagnostics.push(Diagnostic::warning(
+                "color.surface",
+                "Deprecated since 1.2.0, the background color is shaded automatically. Remove this key.",
+            ));
+        }
+
+        if let Some(plugin_check) = PLUGIN_CHECK.get() {
+            check.diagnostics.extend(plugin_check(&settings));
+        }
+
+        check.settings = Some(settings);
+        Ok(check)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+
+    /// The diagnostics in the order of their position in the config file, one per line, like
+    /// `config.yml:3:5: warning: plugin.clock.enabel: Unknown key, it is ignored.`
+    pub fn report(&self) -> String {
+        let mut diagnostics: Vec<(Option<(usize, usize)>, &Diagnostic)> = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.position.or(self.position(&diagnostic.key)),
+                    diagnostic,
+                )
+            })
+            .collect();
+        diagnostics.sort_by_key(|(position, _)| position.unwrap_or((usize::MAX, usize::MAX)));
+
+        let mut report = String::new();
+        for (position, diagnostic) in diagnostics {
+            let location = match position {
+                Some((line, column)) => format!("{}:{}:{}", self.config_file_path, line, column),
+                None => self.config_file_path.clone(),
+            };
+            let severity = match diagnostic.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            let key = match diagnostic.key.as_str() {
+                "" => String::new(),
+                key => format!("{key}: "),
+            };
+            report.push_str(&format!(
+                "{location}: {severity}: {key}{}\n",
+                diagnostic.message
+            ));
+        }
+        report
+    }
+
+    /// Position of the key, or of the closest parent key found, e.g. for keys in flow mappings.
+    fn position(&self, key: &str) -> Option<(usize, usize)> {
+        let mut key = key;
+        loop {
+            if let Some(position) = self.key_positions.get(key) {
+                return Some(*position);
+            }
+            key = key.rsplit_once('.')?.0;
+        }
+    }
+}
+
+/// Removes the markers serde_ignored adds for optional values, e.g. `color.?.accent`.
+fn normalize_key(key: &str) -> String {
+    key.split('.')
+        .filter(|segment| *segment != "?")
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Positions of all keys of the yaml document by dotted path.
+fn key_positions(content: &str) -> HashMap<String, (usize, usize)> {
+    let mut receiver = KeyPositionReceiver::default();
+    // Syntax errors are reported by serde_yaml
+    let _ = yaml_rust2::parser::Parser::new_from_str(content).load(&mut receiver, false);
+    receiver.positions
+}
+
+enum Node {
+    Mapping {
+        path: String,
+        /// The key of the value being read, `None` while reading a key
+        key: Option<String>,
+    },
+    Sequence {
+        path: String,
+        index: usize,
+    },
+}
+
+#[derive(Default)]
+struct KeyPositionReceiver {
+    stack: Vec<Node>,
+    positions: HashMap<String, (usize, usize)>,
+}
+
+impl KeyPositionReceiver {
+    /// Path of the value starting with the current event.
+    fn value_path(&self) -> String {
+        let (path, child) = match self.stack.last() {
+            Some(Node::Mapping { path, key }) => (path, key.clone().unwrap_or_default()),
+            Some(Node::Sequence { path, index }) => (path, index.to_string()),
+            None => return String::new(),
+        };
+        if path.is_empty() {
+            child
+        } else {
+            format!("{path}.{child}")
+        }
+    }
+
+    /// Moves on to the next key of a mapping or the next item of a sequence.
+    fn value_done(&mut self) {
+        match self.stack.last_mut() {
+            Some(Node::Mapping { key, .. }) => *key = None,
+            Some(Node::Sequence { index, .. }) => *index += 1,
+            None => {}
+        }
+    }
+}
+
+impl yaml_rust2::parser::MarkedEventReceiver for KeyPositionReceiver {
+    fn on_event(&mut self, event: yaml_rust2::parser::Event, mark: yaml_rust2::scanner::Marker) {
+        use yaml_rust2::parser::Event;
+
+        match event {
+            Event::Scalar(value, ..) => {
+                if let Some(Node::Mapping {
+                    path,
+                    key: key @ None,
+                }) = self.stack.last_mut()
+                {
+                    let key_path = if path.is_empty() {
+                        value.clone()
+                    } else {
+                        format!("{path}.{value}")
+                    };
+                    // Columns of the marker start at 0
+                    self.positions
+                        .insert(key_path, (mark.line(), mark.col() + 1));
+                    *key = Some(value);
+                } else {
+                    self.value_done();
+                }
+            }
+            Event::Alias(_) => self.value_done(),
+            Event::MappingStart(..) => self.stack.push(Node::Mapping {
+                path: self.value_path(),
+                key: None,
+            }),
+            Event::SequenceStart(..) => self.stack.push(Node::Sequence {
+                path: self.value_path(),
+                index: 0,
+            }),
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.stack.pop();
+                self.value_done();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Deserializes the value along with the dotted paths of the keys not used by `T`.
+pub fn with_unknown_keys<T: serde::de::DeserializeOwned>(
+    value: serde_yaml::Value,
+) -> Result<(T, Vec<String>), serde_yaml::Error> {
+    let mut unknown_keys = vec![];
+    let deserialized = serde_ignored::deserialize(value, |path| {
+        unknown_keys.push(normalize_key(&path.to_string()))
+    })?;
+    Ok((deserialized, unknown_keys))
+}
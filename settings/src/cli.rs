@@ -1,6 +1,6 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
-#[derive(Parser, Debug, Default)]
+#[derive(Parser, Debug, Default, Clone)]
 #[command(author, version = CliArgs::version(), about, long_about=None) ]
 #[command(next_line_help = true)]
 pub struct CliArgs {
@@ -11,6 +11,67 @@ pub struct CliArgs {
         env = "CENTERPIECE_CONFIGURATION_FILE"
     )]
     pub config: Option<String>,
+
+    #[clap(
+        long,
+        help = "Keep running in the background and wait for show / hide / toggle commands"
+    )]
+    pub daemon: bool,
+
+    #[clap(
+        long,
+        conflicts_with = "daemon",
+        help = "Read newline separated items from stdin and print the selected item to stdout"
+    )]
+    pub dmenu: bool,
+
+    #[clap(
+        long,
+        requires = "dmenu",
+        help = "Print the typed query instead of the selected item"
+    )]
+    pub print_query: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    #[command(flatten)]
+    Control(ControlCommand),
+    /// Print the entries matching a query without opening a window
+    Query {
+        /// The text to search for
+        query: String,
+        /// Only print entries of the plugin with this id
+        #[arg(long)]
+        plugin: Option<String>,
+        /// Print the entries as json
+        #[arg(long)]
+        json: bool,
+    },
+    /// Activate an entry without opening a window
+    Activate {
+        /// The entry to activate in the format <plugin>/<entry-id>
+        target: String,
+
+        /// Run one of the additional actions of the entry instead of its default action
+        #[arg(long)]
+        action: Option<String>,
+    },
+    /// Check the config file and report problems
+    CheckConfig,
+}
+
+#[derive(Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Show the window of a running centerpiece daemon
+    Show,
+    /// Hide the window of a running centerpiece daemon
+    Hide,
+    /// Show or hide the window of a running centerpiece daemon
+    Toggle,
 }
 
 impl CliArgs {
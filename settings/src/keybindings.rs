@@ -0,0 +1,228 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Commands which can be bound to a key chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyCommand {
+    SelectNext,
+    SelectPrevious,
+    SelectNextPlugin,
+    SelectPreviousPlugin,
+    SelectFirst,
+    SelectLast,
+    PageUp,
+    PageDown,
+    Activate,
+    OpenActions,
+    Close,
+    ClearQuery,
+    DeleteWord,
+    /// Removes a default binding of the key chord
+    #[serde(rename = "none")]
+    Unbound,
+}
+
+/// A key pressed together with modifiers, written like `ctrl+shift+j`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub struct KeyChord {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub key: egui::Key,
+}
+
+impl KeyChord {
+    pub fn matches(&self, key: egui::Key, modifiers: egui::Modifiers) -> bool {
+        self.key == key
+            && self.ctrl == modifiers.ctrl
+            && self.shift == modifiers.shift
+            && self.alt == modifiers.alt
+    }
+
+    /// Whether the key is pressed with at least the modifiers of the chord.
+    fn matches_with_more_modifiers(&self, key: egui::Key, modifiers: egui::Modifiers) -> bool {
+        self.key == key
+            && (!self.ctrl || modifiers.ctrl)
+            && (!self.shift || modifiers.shift)
+            && (!self.alt || modifiers.alt)
+    }
+
+    fn modifier_count(&self) -> usize {
+        [self.ctrl, self.shift, self.alt]
+            .into_iter()
+            .filter(|modifier| *modifier)
+            .count()
+    }
+}
+
+impl std::str::FromStr for KeyChord {
+    type Err = anyhow::Error;
+
+    fn from_str(chord: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = chord.split('+').map(str::trim).collect();
+        // `ctrl++` binds the plus key
+        if chord.ends_with("++") {
+            parts.truncate(parts.len() - 2);
+            parts.push("+");
+        }
+        let Some((key_name, modifier_names)) = parts.split_last() else {
+            anyhow::bail!("Key chord '{}' is empty.", chord);
+        };
+
+        let mut key_chord = KeyChord {
+            ctrl: false,
+            shift: false,
+            alt: false,
+            key: parse_key(key_name).ok_or_else(|| {
+                anyhow::anyhow!("Unknown key '{key_name}' in key chord '{chord}'.")
+            })?,
+        };
+        for modifier_name in modifier_names {
+            let modifier = match modifier_name.to_lowercase().as_str() {
+                "ctrl" | "control" => &mut key_chord.ctrl,
+                "shift" => &mut key_chord.shift,
+                "alt" => &mut key_chord.alt,
+                _ => anyhow::bail!(
+                    "Unknown modifier '{}' in key chord '{}', expected ctrl, shift or alt.",
+                    modifier_name,
+                    chord
+                ),
+            };
+            if *modifier {
+                anyhow::bail!(
+                    "Modifier '{}' appears twice in key chord '{}'.",
+                    modifier_name,
+                    chord
+                );
+            }
+            *modifier = true;
+        }
+
+        Ok(key_chord)
+    }
+}
+
+impl TryFrom<String> for KeyChord {
+    type Error = anyhow::Error;
+
+    fn try_from(chord: String) -> Result<Self, Self::Error> {
+        chord.parse()
+    }
+}
+
+/// Accepts the key names of egui in any case, e.g. `j`, `enter`, `PageDown` or `down`.
+fn parse_key(name: &str) -> Option<egui::Key> {
+    let name = match name.to_lowercase().as_str() {
+        "esc" => "Escape",
+        "return" => "Enter",
+        _ => name,
+    };
+    egui::Key::from_name(name).or_else(|| {
+        egui::Key::ALL.iter().copied().find(|key| {
+            key.name().eq_ignore_ascii_case(name) || format!("{key:?}").eq_ignore_ascii_case(name)
+        })
+    })
+}
+
+fn default_bindings() -> Vec<(&'static str, KeyCommand)> {
+    vec![
+        ("down", KeyCommand::SelectNext),
+        ("ctrl+j", KeyCommand::SelectNext),
+        ("up", KeyCommand::SelectPrevious),
+        ("ctrl+k", KeyCommand::SelectPrevious),
+        ("ctrl+n", KeyCommand::SelectNextPlugin),
+        ("ctrl+p", KeyCommand::SelectPreviousPlugin),
+        ("ctrl+home", KeyCommand::SelectFirst),
+        ("ctrl+end", KeyCommand::SelectLast),
+        ("pageup", KeyCommand::PageUp),
+        ("pagedown", KeyCommand::PageDown),
+        ("enter", KeyCommand::Activate),
+        ("tab", KeyCommand::OpenActions),
+        ("shift+enter", KeyCommand::OpenActions),
+        ("escape", KeyCommand::Close),
+        ("ctrl+u", KeyCommand::ClearQuery),
+        ("ctrl+w", KeyCommand::DeleteWord),
+    ]
+}
+
+/// Commands by key chord. Configured chords are added to the default chords or replace them.
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct KeybindingSettings {
+    bindings: BTreeMap<KeyChord, KeyCommand>,
+}
+
+impl KeybindingSettings {
+    /// The command bound to a key pressed with the given modifiers.
+    ///
+    /// Chords match their modifiers exactly. Default chords also match while more modifiers
+    /// are held, e.g. `down` with shift held, unless another chord matches exactly.
+    pub fn command(&self, key: egui::Key, modifiers: egui::Modifiers) -> Option<KeyCommand> {
+        let default_bindings: Vec<(KeyChord, KeyCommand)> = default_bindings()
+            .into_iter()
+            .filter_map(|(chord, command)| Some((chord.parse().ok()?, command)))
+            .collect();
+        let mut bindings: BTreeMap<KeyChord, KeyCommand> =
+            default_bindings.iter().copied().collect();
+        bindings.extend(self.bindings.clone());
+
+        let exact_match = bindings
+            .iter()
+            .find(|(chord, _)| chord.matches(key, modifiers));
+        let (_, command) = exact_match.or_else(|| {
+            bindings
+                .iter()
+                .filter(|(chord, _)| {
+                    default_bindings
+                        .iter()
+                        .any(|(default_chord, _)| default_chord == *chord)
+                        && chord.matches_with_more_modifiers(key, modifiers)
+                })
+                .max_by_key(|(chord, _)| chord.modifier_count())
+        })?;
+
+        (*command != KeyCommand::Unbound).then_some(*command)
+    }
+}
+
+/// Reports chords of the keybindings section binding the same keys, e.g. `ctrl+j` and
+/// `control+j`, only one of them would be used.
+pub fn check(keybindings: &serde_yaml::Value) -> Vec<crate::check::Diagnostic> {
+    use crate::check::Diagnostic;
+
+    let Some(keybindings) = keybindings.as_mapping() else {
+        return vec![];
+    };
+    let mut diagnostics = vec![];
+    let mut chords: Vec<(KeyChord, &str, &serde_yaml::Value)> = vec![];
+    for (chord_name, command) in keybindings {
+        // Invalid chords fail to deserialize and are reported already
+        let Some(chord_name) = chord_name.as_str() else {
+            continue;
+        };
+        let Ok(chord) = chord_name.parse::<KeyChord>() else {
+            continue;
+        };
+
+        let key = format!("keybindings.{chord_name}");
+        match chords
+            .iter()
+            .find(|(other_chord, _, _)| *other_chord == chord)
+        {
+            Some((_, other_chord_name, other_command)) if *other_command == command => {
+                diagnostics.push(Diagnostic::warning(
+                    key,
+                    format!("Binds the same keys as '{other_chord_name}', remove one of them."),
+                ));
+            }
+            Some((_, other_chord_name, _)) => diagnostics.push(Diagnostic::error(
+                key,
+                format!("Binds the same keys as '{other_chord_name}' to another command."),
+            )),
+            None => chords.push((chord, chord_name, command)),
+        }
+    }
+    diagnostics
+}
@@ -1,7 +1,10 @@
 use serde::Deserialize;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock, RwLock};
 
+pub mod check;
 pub mod cli;
+pub mod keybindings;
+pub mod window;
 
 pub fn config_directory() -> anyhow::Result<String> {
     let home_directory = std::env::var("HOME")?;
@@ -30,13 +33,15 @@ pub fn centerpiece_cache_directory() -> anyhow::Result<String> {
     Ok(format!("{cache_directory}/centerpiece"))
 }
 
+/// Parses a color code. Colors of the settings are validated when the settings are read,
+/// an invalid color is shown in magenta.
 pub fn hexcolor(color: &str) -> egui::Color32 {
     egui::Color32::from_hex(color).unwrap_or_else(|_| {
-        eprintln!(
+        log::error!(
             "Failed to parse color settings: {} is not a valid color code",
             color
         );
-        std::process::exit(0);
+        egui::Color32::MAGENTA
     })
 }
 
@@ -44,104 +49,6 @@ fn default_true() -> bool {
     true
 }
 
-fn default_false() -> bool {
-    false
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ApplicationsPluginSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
-}
-
-impl Default for ApplicationsPluginSettings {
-    fn default() -> Self {
-        Self { enable: true }
-    }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct BraveBookmarksPluginSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
-}
-
-impl Default for BraveBookmarksPluginSettings {
-    fn default() -> Self {
-        Self { enable: true }
-    }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct BraveHistoryPluginSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
-}
-
-impl Default for BraveHistoryPluginSettings {
-    fn default() -> Self {
-        Self { enable: true }
-    }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct BraveProgressiveWebAppsSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
-}
-
-impl Default for BraveProgressiveWebAppsSettings {
-    fn default() -> Self {
-        Self { enable: true }
-    }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ClockPluginSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
-}
-
-impl Default for ClockPluginSettings {
-    fn default() -> Self {
-        Self { enable: true }
-    }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct FirefoxBookmarksPluginSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
-}
-
-impl Default for FirefoxBookmarksPluginSettings {
-    fn default() -> Self {
-        Self { enable: true }
-    }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct FirefoxHistoryPluginSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
-}
-
-impl Default for FirefoxHistoryPluginSettings {
-    fn default() -> Self {
-        Self { enable: true }
-    }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct GitRepositoriesPluginSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
-    #[serde(default = "default_true")]
-    pub zoxide: bool,
-    #[serde(default = "default_commands")]
-    pub commands: Vec<Vec<String>>,
-}
-
 fn default_white() -> String {
     "#ffffff".into()
 }
@@ -166,6 +73,35 @@ pub struct ColorSettings {
     )]
     #[serde(default = "default_deprecated")]
     pub surface: String,
+    /// Color of the characters matching the query, defaults to the text color
+    #[serde(default)]
+    pub accent: Option<String>,
+    /// Outline of the selected entry, defaults to the text color
+    #[serde(default)]
+    pub selection: Option<String>,
+    /// Color of secondary text like subtitles, defaults to a faded text color
+    #[serde(default)]
+    pub muted: Option<String>,
+    /// Color of the lines between sections, defaults to a faded text color
+    #[serde(default)]
+    pub separator: Option<String>,
+}
+
+impl ColorSettings {
+    /// The configured colors by key.
+    pub fn colors(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("text", Some(&self.text)),
+            ("background", Some(&self.background)),
+            ("accent", self.accent.as_ref()),
+            ("selection", self.selection.as_ref()),
+            ("muted", self.muted.as_ref()),
+            ("separator", self.separator.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(key, color)| Some((key, color?.as_str())))
+        .collect()
+    }
 }
 
 impl Default for ColorSettings {
@@ -175,10 +111,42 @@ impl Default for ColorSettings {
             text: default_white(),
             background: default_black(),
             surface: default_deprecated(),
+            accent: None,
+            selection: None,
+            muted: None,
+            separator: None,
         }
     }
 }
 
+/// Settings every plugin instance accepts next to the settings of its plugin.
+///
+/// Unset fields fall back to the defaults of the plugin.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct PluginInstanceSettings {
+    /// Id of the plugin to run, defaults to the id of the instance
+    #[serde(default, rename = "type")]
+    pub plugin: Option<String>,
+    #[serde(default)]
+    pub enable: Option<bool>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub keywords: Option<Vec<String>>,
+    /// Plugins with a higher priority are shown first
+    #[serde(default)]
+    pub priority: Option<u32>,
+    /// Number of entries shown at most, all matching entries if unset
+    #[serde(default)]
+    pub max_results: Option<usize>,
+    /// Number of characters the query needs before entries are shown
+    #[serde(default)]
+    pub min_query_length: Option<usize>,
+    /// Only show entries while a keyword scopes the search to the plugin
+    #[serde(default)]
+    pub only_when_scoped: Option<bool>,
+}
+
 fn default_commands() -> Vec<Vec<String>> {
     vec![
         vec![
@@ -195,236 +163,216 @@ fn default_commands() -> Vec<Vec<String>> {
     ]
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GitRepositoriesPluginSettings {
+    #[serde(default = "default_true")]
+    pub zoxide: bool,
+    #[serde(default = "default_commands")]
+    pub commands: Vec<Vec<String>>,
+}
+
 impl Default for GitRepositoriesPluginSettings {
     fn default() -> Self {
         Self {
-            enable: true,
             zoxide: true,
             commands: default_commands(),
         }
     }
 }
 
-#[derive(Debug, Deserialize, Default)]
-pub struct GitmojiPluginSettings {
-    #[serde(default = "default_false")]
-    pub enable: bool,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ResourceMonitorBatteryPluginSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
-}
-
-impl Default for ResourceMonitorBatteryPluginSettings {
-    fn default() -> Self {
-        Self { enable: true }
-    }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ResourceMonitorCpuPluginSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
-}
-
-impl Default for ResourceMonitorCpuPluginSettings {
-    fn default() -> Self {
-        Self { enable: true }
-    }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ResourceMonitorDisksSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
-}
-
-impl Default for ResourceMonitorDisksSettings {
-    fn default() -> Self {
-        Self { enable: true }
-    }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ResourceMonitorMemoryPluginSettings {
+#[derive(Debug, Default, Deserialize)]
+pub struct ScriptPluginSettings {
+    /// The executable and its arguments
+    #[serde(default)]
+    pub command: Vec<String>,
+    /// Fuzzy match the entries of the script against the query. Disable for scripts which
+    /// filter their entries themselves.
     #[serde(default = "default_true")]
-    pub enable: bool,
-}
-
-impl Default for ResourceMonitorMemoryPluginSettings {
-    fn default() -> Self {
-        Self { enable: true }
-    }
+    pub filter: bool,
+    /// Seconds between timeout requests sent to the script to refresh its entries
+    #[serde(default)]
+    pub update_interval: Option<u64>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct SystemPluginSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutMode {
+    /// Entries are grouped by plugin, plugins are ordered by priority
+    #[default]
+    Grouped,
+    /// Entries of all plugins are merged and ordered by how well they match the query
+    Flat,
 }
 
-impl Default for SystemPluginSettings {
-    fn default() -> Self {
-        Self { enable: true }
-    }
+#[derive(Debug, Default, Deserialize)]
+pub struct LayoutSettings {
+    #[serde(default)]
+    pub mode: LayoutMode,
+    /// Added to the match score of every entry of a plugin in the flat layout
+    #[serde(default)]
+    pub plugin_weights: std::collections::HashMap<String, i32>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct WifiPluginSettings {
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct IconSettings {
     #[serde(default = "default_true")]
     pub enable: bool,
+    /// Name of the icon theme searched before the hicolor fallback theme, e.g. `Papirus`
+    #[serde(default)]
+    pub theme: Option<String>,
 }
 
-impl Default for WifiPluginSettings {
+impl Default for IconSettings {
     fn default() -> Self {
-        Self { enable: true }
+        Self {
+            enable: true,
+            theme: None,
+        }
     }
 }
 
-#[derive(Debug, Deserialize)]
-pub struct NiriWindowsPluginSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
+fn default_font_size() -> f32 {
+    14.
 }
 
-impl Default for NiriWindowsPluginSettings {
-    fn default() -> Self {
-        Self { enable: true }
-    }
-}
-
-#[derive(Debug, Deserialize)]
-pub struct SwayWindowsPluginSettings {
-    #[serde(default = "default_true")]
-    pub enable: bool,
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct FontSettings {
+    /// Name of an installed font family, looked up with fontconfig
+    #[serde(default)]
+    pub family: Option<String>,
+    /// Path to a ttf or otf file, takes precedence over the family
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Size of the text in logical pixels, spacings scale with it
+    #[serde(default = "default_font_size")]
+    pub size: f32,
 }
 
-impl Default for SwayWindowsPluginSettings {
+impl Default for FontSettings {
     fn default() -> Self {
-        Self { enable: true }
+        Self {
+            family: None,
+            file: None,
+            size: default_font_size(),
+        }
     }
 }
 
+/// Sizes are in logical pixels and scale with the font size if unset.
 #[derive(Debug, Default, Deserialize)]
-pub struct PluginSettings {
-    #[serde(default)]
-    pub applications: ApplicationsPluginSettings,
-    #[serde(default)]
-    pub brave_bookmarks: BraveBookmarksPluginSettings,
+pub struct ThemeSettings {
     #[serde(default)]
-    pub brave_history: BraveHistoryPluginSettings,
+    pub font: FontSettings,
+    /// Space between the outline of an entry and its content
     #[serde(default)]
-    pub brave_progressive_web_apps: BraveProgressiveWebAppsSettings,
+    pub row_padding: Option<f32>,
+    /// Corner radius of the window
     #[serde(default)]
-    pub clock: ClockPluginSettings,
+    pub corner_radius: Option<f32>,
+    /// Corner radius of the outline of an entry
     #[serde(default)]
-    pub firefox_bookmarks: FirefoxBookmarksPluginSettings,
-    #[serde(default)]
-    pub firefox_history: FirefoxHistoryPluginSettings,
-    #[serde(default)]
-    pub git_repositories: GitRepositoriesPluginSettings,
-    #[serde(default)]
-    pub gitmoji: GitmojiPluginSettings,
-    #[serde(default)]
-    pub resource_monitor_battery: ResourceMonitorBatteryPluginSettings,
+    pub row_corner_radius: Option<f32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Settings {
+    /// Config sections of the plugin instances by instance id
     #[serde(default)]
-    pub resource_monitor_cpu: ResourceMonitorCpuPluginSettings,
+    pub plugin: std::collections::BTreeMap<String, serde_yaml::Value>,
     #[serde(default)]
-    pub resource_monitor_disks: ResourceMonitorDisksSettings,
+    pub color: ColorSettings,
     #[serde(default)]
-    pub resource_monitor_memory: ResourceMonitorMemoryPluginSettings,
+    pub layout: LayoutSettings,
     #[serde(default)]
-    pub niri_windows: NiriWindowsPluginSettings,
+    pub icon: IconSettings,
     #[serde(default)]
-    pub sway_windows: SwayWindowsPluginSettings,
+    pub keybindings: keybindings::KeybindingSettings,
     #[serde(default)]
-    pub system: SystemPluginSettings,
+    pub window: window::WindowSettings,
     #[serde(default)]
-    pub wifi: WifiPluginSettings,
+    pub theme: ThemeSettings,
 }
 
-#[derive(Debug, Default, Deserialize)]
-pub struct Settings {
-    #[serde(default)]
-    pub plugin: PluginSettings,
-    #[serde(default)]
-    pub color: ColorSettings,
+/// Config file passed on the command line, the default config file is read if unset.
+static CONFIG_FILE_PATH: OnceLock<String> = OnceLock::new();
+
+static SETTINGS: RwLock<Option<Arc<Settings>>> = RwLock::new(None);
+
+/// Path of the config file the settings are read from.
+pub fn config_file_path() -> anyhow::Result<String> {
+    match CONFIG_FILE_PATH.get() {
+        Some(config_file_path) => Ok(config_file_path.clone()),
+        None => centerpiece_default_config_path(),
+    }
 }
 
-impl Settings {
-    pub fn new() -> Self {
-        let config_directory_result = centerpiece_config_directory();
-        if let Err(error) = config_directory_result {
-            log::error!(
-            error = log::error!("{:?}", error);
-            "Unable to find config directory.",
-            );
-            panic!();
-        }
-        let config_directory = config_directory_result.unwrap();
-        let config_file_path = format!("{config_directory}/config.yml");
+/// Reads the settings from this config file instead of the default config file.
+///
+/// Only the first path set is used.
+pub fn set_config_file_path(config_file_path: String) {
+    let _ = CONFIG_FILE_PATH.set(config_file_path);
+}
 
-        let config_file_result = std::fs::File::open(config_file_path);
-        if config_file_result.is_err() {
-            log::info!("No custom config file found, falling back to default.");
-            return Self::default();
-        }
-        let config_file = config_file_result.unwrap();
-        let config_result = serde_yaml::from_reader(config_file);
-        if let Err(error) = config_result {
-            log::error!(
-            error = log::error!("{:?}", error);
-            "Config file does not match settings struct.",
-            );
-            panic!();
-        }
-        let settings: Settings = config_result.unwrap();
+impl Settings {
+    /// Reads the settings from the config file, or fails with the problems found in it.
+    pub fn new() -> anyhow::Result<Self> {
+        let (settings, _warnings) = Self::read(&config_file_path()?)?;
+        Ok(settings)
+    }
 
-        #[allow(deprecated)]
-        if settings.color.surface != *"deprecated" {
-            log::warn!(
-                "color.surface has been replaced by automatic shading of the background color in cernterpiece version 1.2.0. Please remove this field from your configuration."
-            )
+    /// The current settings, read from the config file on first use.
+    ///
+    /// The defaults are used if the config file has errors, they are reported at startup.
+    pub fn get_or_init() -> Arc<Self> {
+        if let Some(settings) = SETTINGS
+            .read()
+            .unwrap_or_else(|error| error.into_inner())
+            .as_ref()
+        {
+            return settings.clone();
         }
 
+        let mut settings = SETTINGS.write().unwrap_or_else(|error| error.into_inner());
         settings
+            .get_or_insert_with(|| {
+                Arc::new(Self::new().unwrap_or_else(|error| {
+                    log::error!("Using the default settings: {:#}", error);
+                    Self::default()
+                }))
+            })
+            .clone()
     }
 
-    pub fn get_or_init() -> &'static Self {
-        static SETTINGS: OnceLock<Settings> = OnceLock::new();
-        SETTINGS.get_or_init(Self::new)
+    /// Reads the config file again and replaces the current settings.
+    ///
+    /// The current settings are kept if the config file has errors.
+    pub fn reload() -> anyhow::Result<()> {
+        let config_file_path = config_file_path()?;
+        let (settings, warnings) = Self::read(&config_file_path)?;
+        if !std::path::Path::new(&config_file_path).exists() {
+            log::warn!(
+                "Config file '{}' was removed, using the default settings.",
+                config_file_path
+            );
+        }
+        if !warnings.is_empty() {
+            log::warn!("{}", warnings.trim_end());
+        }
+        *SETTINGS.write().unwrap_or_else(|error| error.into_inner()) = Some(Arc::new(settings));
+        Ok(())
     }
-}
 
-impl std::convert::TryFrom<crate::cli::CliArgs> for Settings {
-    type Error = anyhow::Error;
-
-    fn try_from(args: crate::cli::CliArgs) -> Result<Self, Self::Error> {
-        let maybe_config_file_path = args.config;
-        let config_file_path = maybe_config_file_path.unwrap_or_else(|| {
-            centerpiece_default_config_path().unwrap_or_else(|error| {
-                log::error!(
-                    error = log::error!("{:?}", error);
-                    "Unable to find default config file.",
-                );
-                panic!();
-            })
-        });
-        let config_file_result = std::fs::File::open(config_file_path);
-        if config_file_result.is_err() {
-            log::info!("No custom config file found, falling back to default.");
-            return Ok(Self::default());
-        }
-        let config_file = config_file_result?;
-        let config_result = serde_yaml::from_reader(config_file);
-        if let Err(ref error) = config_result {
-            log::error!(
-            error = log::error!("{:?}", error);
-            "Config file does not match settings struct.",
-            );
+    /// Reads the config file, reporting its problems like `centerpiece check-config`.
+    ///
+    /// Returns the settings together with the report of the warnings, fails with the report
+    /// if the config file has errors.
+    fn read(config_file_path: &str) -> anyhow::Result<(Self, String)> {
+        let check = check::Check::of(config_file_path)?;
+        let report = check.report();
+        let has_errors = check.has_errors();
+        match check.settings {
+            Some(settings) if !has_errors => Ok((settings, report)),
+            _ => anyhow::bail!("{}", report.trim_end()),
         }
-        Ok(config_result?)
     }
 }
@@ -0,0 +1,151 @@
+use serde::Deserialize;
+
+/// A width or height in logical pixels, or relative to the size of the output.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "LengthValue")]
+pub enum Length {
+    Pixels(u32),
+    /// Percentage of the size of the output, written like `50%`
+    Percent(f32),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LengthValue {
+    Number(i64),
+    Text(String),
+}
+
+impl TryFrom<LengthValue> for Length {
+    type Error = anyhow::Error;
+
+    fn try_from(value: LengthValue) -> Result<Self, Self::Error> {
+        let text = match value {
+            LengthValue::Number(number) => number.to_string(),
+            LengthValue::Text(text) => text,
+        };
+        let invalid = || {
+            anyhow::anyhow!(
+                "Invalid length '{}', expected a number of pixels or a percentage like '50%'.",
+                text
+            )
+        };
+
+        match text.trim().strip_suffix('%') {
+            Some(percent) => {
+                let percent: f32 = percent.trim().parse().map_err(|_| invalid())?;
+                if !(0. ..=100.).contains(&percent) {
+                    anyhow::bail!("Percentage '{}' is not between 0% and 100%.", text);
+                }
+                Ok(Length::Percent(percent))
+            }
+            None => Ok(Length::Pixels(text.trim().parse().map_err(|_| invalid())?)),
+        }
+    }
+}
+
+impl Length {
+    /// The length in logical pixels, `None` for a percentage while the output size is unknown.
+    pub fn pixels(&self, output_length: Option<u32>) -> Option<u32> {
+        match self {
+            Length::Pixels(pixels) => Some(*pixels),
+            Length::Percent(percent) => {
+                output_length.map(|length| (length as f32 * percent / 100.).round() as u32)
+            }
+        }
+    }
+}
+
+/// Edge of the output the window is attached to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Anchor {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Layer {
+    /// Above normal windows, below fullscreen windows
+    #[default]
+    Top,
+    /// Above all windows, including fullscreen windows
+    Overlay,
+}
+
+/// Distances in logical pixels to the anchored edges of the output.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct Margin {
+    #[serde(default)]
+    pub top: i32,
+    #[serde(default)]
+    pub right: i32,
+    #[serde(default)]
+    pub bottom: i32,
+    #[serde(default)]
+    pub left: i32,
+}
+
+fn default_width() -> Length {
+    Length::Pixels(800)
+}
+
+fn default_height() -> Length {
+    Length::Pixels(600)
+}
+
+fn default_output() -> String {
+    "focused".into()
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct WindowSettings {
+    #[serde(default = "default_width")]
+    pub width: Length,
+    #[serde(default = "default_height")]
+    pub height: Length,
+    /// Edges the window is attached to, the window is centered on the output without anchors
+    #[serde(default)]
+    pub anchor: Vec<Anchor>,
+    /// Only applies to anchored edges
+    #[serde(default)]
+    pub margin: Margin,
+    #[serde(default)]
+    pub layer: Layer,
+    /// Name of the output to open the window on, e.g. `DP-1`, or `focused` to let the
+    /// compositor pick the focused output
+    #[serde(default = "default_output")]
+    pub output: String,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            width: default_width(),
+            height: default_height(),
+            anchor: Vec::new(),
+            margin: Margin::default(),
+            layer: Layer::default(),
+            output: default_output(),
+        }
+    }
+}
+
+impl WindowSettings {
+    /// Name of the configured output, `None` for the focused output.
+    pub fn output_name(&self) -> Option<&str> {
+        (self.output != "focused").then_some(self.output.as_str())
+    }
+
+    /// The window size in logical pixels for an output of the given logical size.
+    ///
+    /// Percentages fall back to the default size while the output size is unknown.
+    pub fn size(&self, output_size: Option<(u32, u32)>) -> (u32, u32) {
+        let width = self.width.pixels(output_size.map(|(width, _)| width));
+        let height = self.height.pixels(output_size.map(|(_, height)| height));
+        (width.unwrap_or(800).max(1), height.unwrap_or(600).max(1))
+    }
+}